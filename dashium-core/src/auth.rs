@@ -0,0 +1,265 @@
+//! GJP2 verification with an in-memory cache.
+//!
+//! Verifying a GJP2 means running Argon2, which is far too slow to do on the async executor for
+//! almost every request. The hashing runs on the blocking pool instead, and successful
+//! verifications are remembered for `AUTH_CACHE_TTL` seconds, for at most `AUTH_CACHE_SIZE`
+//! accounts. Anything that changes a password must call [`invalidate`].
+//!
+//! Handlers get the client's credentials through the [`AuthForm`] extractor, which accepts both
+//! the 2.2 `gjp2` and the XOR-encoded `gjp` sent by 2.1 clients.
+
+use std::{
+    collections::HashMap,
+    sync::{
+        LazyLock, Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use anyhow::Result;
+use argon2::{
+    Argon2,
+    password_hash::{PasswordHash, PasswordVerifier, SaltString, rand_core::OsRng},
+};
+use axum::{
+    body::Bytes,
+    extract::{FromRequest, Request},
+};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, de::DeserializeOwned};
+use sha2::Sha256;
+use sqlx::PgPool;
+
+use crate::{
+    AppError,
+    config::config,
+    models::{Restriction, RestrictionKind, User},
+    util::{base64_decode, cyclic_xor, salt_and_sha1},
+};
+
+static CACHE: LazyLock<AuthCache> = LazyLock::new(AuthCache::new);
+
+struct AuthCache {
+    /// Random per-process salt, so the cache never holds a usable GJP2.
+    salt: String,
+    entries: Mutex<HashMap<i32, CacheEntry>>,
+    /// Bumped whenever an entry is removed, so verifications that started before can't put
+    /// back a password that was just changed.
+    epoch: AtomicU64,
+}
+
+struct CacheEntry {
+    gjp2_hash: String,
+    expires_at: Instant,
+}
+
+impl AuthCache {
+    fn new() -> Self {
+        Self {
+            salt: SaltString::generate(&mut OsRng).to_string(),
+            entries: Mutex::new(HashMap::new()),
+            epoch: AtomicU64::new(0),
+        }
+    }
+
+    fn epoch(&self) -> u64 {
+        self.epoch.load(Ordering::SeqCst)
+    }
+
+    fn contains(&self, user_id: i32, gjp2: &str) -> bool {
+        let gjp2_hash = salt_and_sha1(gjp2, &self.salt);
+        let entries = self.entries.lock().unwrap();
+
+        entries
+            .get(&user_id)
+            .is_some_and(|e| e.gjp2_hash == gjp2_hash && e.expires_at > Instant::now())
+    }
+
+    /// Caches a verification that started at `epoch`, unless an entry was removed since.
+    fn insert(&self, user_id: i32, gjp2: &str, epoch: u64) {
+        let capacity = config().auth_cache_size;
+
+        if capacity == 0 {
+            return;
+        }
+
+        let now = Instant::now();
+        let entry = CacheEntry {
+            gjp2_hash: salt_and_sha1(gjp2, &self.salt),
+            expires_at: now + Duration::from_secs(config().auth_cache_ttl),
+        };
+
+        let mut entries = self.entries.lock().unwrap();
+
+        if self.epoch() != epoch {
+            return;
+        }
+
+        if entries.len() >= capacity && !entries.contains_key(&user_id) {
+            entries.retain(|_, e| e.expires_at > now);
+        }
+
+        if entries.len() >= capacity && !entries.contains_key(&user_id) {
+            let oldest = entries
+                .iter()
+                .min_by_key(|(_, e)| e.expires_at)
+                .map(|(&id, _)| id);
+
+            if let Some(oldest) = oldest {
+                entries.remove(&oldest);
+            }
+        }
+
+        entries.insert(user_id, entry);
+    }
+
+    fn remove(&self, user_id: i32) {
+        let mut entries = self.entries.lock().unwrap();
+
+        self.epoch.fetch_add(1, Ordering::SeqCst);
+        entries.remove(&user_id);
+    }
+}
+
+/// Returns `true` if the given GJP2 matches the one stored in the database.
+pub async fn verify_gjp2(pool: &PgPool, user_id: i32, gjp2: &str) -> Result<bool> {
+    if CACHE.contains(user_id, gjp2) {
+        return Ok(true);
+    }
+
+    let epoch = CACHE.epoch();
+
+    let Some(hash) = sqlx::query_scalar!("SELECT hash FROM users WHERE id = $1", user_id)
+        .fetch_optional(pool)
+        .await?
+    else {
+        return Ok(false);
+    };
+
+    let password = gjp2.to_string();
+    let is_valid = tokio::task::spawn_blocking(move || {
+        let hash = PasswordHash::new(&hash).expect("Failed to parse hash");
+        Argon2::default()
+            .verify_password(password.as_bytes(), &hash)
+            .is_ok()
+    })
+    .await?;
+
+    if is_valid {
+        CACHE.insert(user_id, gjp2, epoch);
+    }
+
+    Ok(is_valid)
+}
+
+/// Forgets the cached verification of a user. Must be called whenever their password changes.
+pub fn invalidate(user_id: i32) {
+    CACHE.remove(user_id);
+}
+
+/// Signs the activation link of a user, valid until the `expires_at` Unix timestamp, with the
+/// server's secret key.
+pub fn activation_signature(user_id: i32, expires_at: i64) -> String {
+    hex::encode(activation_mac(user_id, expires_at).finalize().into_bytes())
+}
+
+/// Checks the signature of an activation link, in constant time.
+pub fn verify_activation_signature(user_id: i32, expires_at: i64, signature: &str) -> bool {
+    hex::decode(signature).is_ok_and(|signature| {
+        activation_mac(user_id, expires_at)
+            .verify_slice(&signature)
+            .is_ok()
+    })
+}
+
+fn activation_mac(user_id: i32, expires_at: i64) -> Hmac<Sha256> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(config().secret_key.as_bytes())
+        .expect("HMAC accepts keys of any length");
+    mac.update(format!("activate{user_id}:{expires_at}").as_bytes());
+    mac
+}
+
+/// Returns `true` if an account may be used: it is activated, when activation is required,
+/// and not banned.
+pub async fn is_usable(pool: &PgPool, user_id: i32) -> Result<bool> {
+    if config().account_activation && !User::is_activated(pool, user_id).await? {
+        return Ok(false);
+    }
+
+    if Restriction::is_restricted(pool, user_id, RestrictionKind::Account).await? {
+        return Ok(false);
+    }
+
+    Ok(true)
+}
+
+/// Turns a plain text password into its GJP2.
+pub fn password_to_gjp2(password: &str) -> String {
+    salt_and_sha1(password, "mI29fmAnxgTs")
+}
+
+/// Decodes a legacy GJP (the password XORed with `37526`, then Base64 encoded) into a GJP2.
+pub fn gjp_to_gjp2(gjp: &str) -> Option<String> {
+    let password = cyclic_xor(&base64_decode(gjp).ok()?, b"37526").ok()?;
+    Some(password_to_gjp2(&password))
+}
+
+/// The credentials sent along with an authenticated request.
+#[derive(Deserialize, Debug)]
+pub struct Auth {
+    #[serde(rename = "accountID", default)]
+    pub account_id: i32,
+    #[serde(default)]
+    gjp: String,
+    #[serde(default)]
+    gjp2: String,
+}
+
+impl Auth {
+    /// Returns the GJP2 the client authenticated with, if any.
+    pub fn gjp2(&self) -> Option<String> {
+        if !self.gjp2.is_empty() {
+            return Some(self.gjp2.clone());
+        }
+
+        if !self.gjp.is_empty() {
+            return gjp_to_gjp2(&self.gjp);
+        }
+
+        None
+    }
+
+    /// Returns `true` if the credentials are valid for `accountID` and the account may be used.
+    pub async fn verify(&self, pool: &PgPool) -> Result<bool> {
+        let Some(gjp2) = self.gjp2() else {
+            return Ok(false);
+        };
+
+        if !verify_gjp2(pool, self.account_id, &gjp2).await? {
+            return Ok(false);
+        }
+
+        is_usable(pool, self.account_id).await
+    }
+}
+
+/// Extracts a form along with the [`Auth`] credentials contained in it.
+pub struct AuthForm<T>(pub Auth, pub T);
+
+impl<S, T> FromRequest<S> for AuthForm<T>
+where
+    S: Send + Sync,
+    T: DeserializeOwned,
+{
+    type Rejection = AppError;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let body = Bytes::from_request(req, state).await?;
+
+        let auth = serde_html_form::from_bytes(&body)?;
+        let form = serde_html_form::from_bytes(&body)?;
+
+        Ok(Self(auth, form))
+    }
+}
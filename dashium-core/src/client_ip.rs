@@ -0,0 +1,70 @@
+//! Extractor for the IP address of the client.
+//!
+//! When `TRUST_PROXY` is enabled the address is taken from the `X-Forwarded-For` or `X-Real-IP`
+//! headers set by a reverse proxy, otherwise from the connection itself. Never enable it when
+//! the server is reachable directly, since clients could then pick their own address.
+//!
+//! Clients can send their own `X-Forwarded-For`, which proxies append to, so only the right-most
+//! entry that isn't one of the `TRUSTED_PROXIES` is used.
+
+use std::net::{IpAddr, SocketAddr};
+
+use anyhow::anyhow;
+use axum::{
+    extract::{ConnectInfo, FromRequestParts},
+    http::request::Parts,
+};
+
+use crate::{AppError, config::config};
+
+/// The IP address of the client.
+pub struct ClientIp(pub IpAddr);
+
+impl<S> FromRequestParts<S> for ClientIp
+where
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        if config().trust_proxy
+            && let Some(ip) = forwarded_ip(parts)
+        {
+            return Ok(Self(ip));
+        }
+
+        let ip = parts
+            .extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| addr.ip().to_canonical())
+            .ok_or_else(|| anyhow!("the client address is unavailable"))?;
+
+        Ok(Self(ip))
+    }
+}
+
+fn forwarded_ip(parts: &Parts) -> Option<IpAddr> {
+    if let Some(forwarded) = parts.headers.get("x-forwarded-for") {
+        let trusted = &config().trusted_proxies;
+
+        // An entry that doesn't parse ends the search, and the connection address is used instead
+        return forwarded
+            .to_str()
+            .ok()?
+            .rsplit(',')
+            .map(parse_ip)
+            .find(|ip| !ip.is_some_and(|ip| trusted.contains(&ip)))?;
+    }
+
+    parse_ip(parts.headers.get("x-real-ip")?.to_str().ok()?)
+}
+
+/// Parses an address from a proxy header, which may come with a port.
+fn parse_ip(ip: &str) -> Option<IpAddr> {
+    let ip = ip.trim();
+
+    ip.parse::<IpAddr>()
+        .or_else(|_| ip.parse::<SocketAddr>().map(|addr| addr.ip()))
+        .ok()
+        .map(|ip| ip.to_canonical())
+}
@@ -0,0 +1,222 @@
+use std::{env, net::IpAddr, path::PathBuf, str::FromStr, sync::LazyLock};
+
+use anyhow::{Result, bail};
+
+static CONFIG: LazyLock<Config> = LazyLock::new(Config::from_env);
+
+/// Server settings read from the environment (or the `.env` file).
+pub struct Config {
+    /// Public URL the GD client uses to reach this server.
+    pub server_url: String,
+    /// Directory the custom song files are served from.
+    pub songs_dir: PathBuf,
+    /// Whether to take client IPs from the `X-Forwarded-For` header of a reverse proxy.
+    pub trust_proxy: bool,
+    /// Proxies skipped when reading `X-Forwarded-For`, besides the one the server is behind.
+    pub trusted_proxies: Vec<IpAddr>,
+    /// Number of days of downloads the trending search looks at.
+    pub trending_days: i32,
+    /// Hour of the day (UTC) the daily, weekly and event levels rotate at.
+    pub rotation_hour: u32,
+    /// Seconds between two openings of the small and large chests.
+    pub small_chest_wait: i64,
+    pub large_chest_wait: i64,
+    /// What the small and large chests can contain.
+    pub small_chest: ChestRewards,
+    pub large_chest: ChestRewards,
+    /// Number of reports after which a level is hidden from searches until reviewed.
+    pub report_threshold: i64,
+    /// Root directory of the level store.
+    pub levels_dir: PathBuf,
+    /// Level store backend, either `fs` or `compressed`.
+    pub level_store: String,
+    /// Maximum size of a decompressed level string, in bytes.
+    pub max_level_size: usize,
+    /// Maximum number of objects in an uploaded level.
+    pub max_level_objects: usize,
+    /// Number of save data backups kept per account, at least 1.
+    pub save_backup_versions: i64,
+    /// Maximum size of an uploaded save data backup, in bytes.
+    pub max_save_size: usize,
+    /// Stars, moons, demons and secret coins obtainable outside of online levels
+    /// (main levels, the tower, ...), on top of what rated levels give.
+    pub official_stars: i32,
+    pub official_moons: i32,
+    pub official_demons: i32,
+    pub official_coins: i32,
+    /// Whether `updateGJUserScore22` rejects submissions with a bad `seed2`. When disabled they
+    /// are only flagged, which makes it possible to check the `seed2` computation first.
+    pub verify_seed2: bool,
+    /// Rejected stat submissions within `LEADERBOARD_BAN_WINDOW` days that ban a user
+    /// from the leaderboards.
+    pub leaderboard_ban_threshold: i64,
+    pub leaderboard_ban_window: i32,
+    /// Maximum number of accounts kept in the authentication cache.
+    pub auth_cache_size: usize,
+    /// How long a successful GJP2 verification is cached, in seconds.
+    pub auth_cache_ttl: u64,
+    /// Email backend, either `smtp`, `file` or `log`.
+    pub mailer: String,
+    /// Directory the `file` mailer writes emails to.
+    pub mail_dir: PathBuf,
+    /// Address outgoing emails are sent from.
+    pub smtp_from: String,
+    pub smtp_host: String,
+    pub smtp_port: u16,
+    /// Either `none`, `starttls` or `tls`.
+    pub smtp_tls: String,
+    pub smtp_username: String,
+    pub smtp_password: String,
+    /// How long a password reset link stays valid, in seconds.
+    pub password_reset_ttl: i64,
+    /// Whether new accounts have to be activated through an emailed link before use.
+    pub account_activation: bool,
+    /// How long an activation link stays valid, in seconds. Accounts that weren't activated
+    /// in time can be registered again.
+    pub activation_link_ttl: i64,
+    /// Key used to sign links sent to users. Required when account activation is enabled.
+    pub secret_key: String,
+    /// Settings whose value couldn't be read, and were replaced by their default.
+    invalid: Vec<&'static str>,
+}
+
+/// The contents a chest is rolled from. Ranges are inclusive and written as `min-max`
+/// in the environment.
+pub struct ChestRewards {
+    pub orbs: (u32, u32),
+    pub diamonds: (u32, u32),
+    /// Percent chance of getting a random shard.
+    pub shard_chance: u32,
+    pub keys: (u32, u32),
+}
+
+impl Config {
+    fn from_env() -> Self {
+        let mut invalid = Vec::new();
+
+        Self {
+            server_url: var_or("SERVER_URL", "http://127.0.0.1:2207"),
+            songs_dir: var_or("SONGS_DIR", "./data/songs").into(),
+            trust_proxy: parse_or("TRUST_PROXY", false, &mut invalid),
+            trusted_proxies: proxies_or_empty("TRUSTED_PROXIES", &mut invalid),
+            trending_days: parse_or("TRENDING_DAYS", 7, &mut invalid),
+            rotation_hour: parse_or("ROTATION_HOUR", 0, &mut invalid),
+            small_chest_wait: parse_or("SMALL_CHEST_WAIT", 14400, &mut invalid),
+            large_chest_wait: parse_or("LARGE_CHEST_WAIT", 86400, &mut invalid),
+            small_chest: ChestRewards {
+                orbs: range_or("SMALL_CHEST_ORBS", (200, 400), &mut invalid),
+                diamonds: range_or("SMALL_CHEST_DIAMONDS", (2, 10), &mut invalid),
+                shard_chance: parse_or("SMALL_CHEST_SHARD_CHANCE", 25, &mut invalid),
+                keys: range_or("SMALL_CHEST_KEYS", (0, 1), &mut invalid),
+            },
+            large_chest: ChestRewards {
+                orbs: range_or("LARGE_CHEST_ORBS", (1000, 2000), &mut invalid),
+                diamonds: range_or("LARGE_CHEST_DIAMONDS", (20, 40), &mut invalid),
+                shard_chance: parse_or("LARGE_CHEST_SHARD_CHANCE", 100, &mut invalid),
+                keys: range_or("LARGE_CHEST_KEYS", (1, 2), &mut invalid),
+            },
+            report_threshold: parse_or("REPORT_THRESHOLD", 10, &mut invalid),
+            levels_dir: var_or("LEVELS_DIR", "./data/levels").into(),
+            level_store: var_or("LEVEL_STORE", "fs"),
+            max_level_size: parse_or("MAX_LEVEL_SIZE", 8388608, &mut invalid),
+            max_level_objects: parse_or("MAX_LEVEL_OBJECTS", 400000, &mut invalid),
+            save_backup_versions: parse_or("SAVE_BACKUP_VERSIONS", 5, &mut invalid).max(1),
+            max_save_size: parse_or("MAX_SAVE_SIZE", 4194304, &mut invalid),
+            official_stars: parse_or("OFFICIAL_STARS", 200, &mut invalid),
+            official_moons: parse_or("OFFICIAL_MOONS", 30, &mut invalid),
+            official_demons: parse_or("OFFICIAL_DEMONS", 5, &mut invalid),
+            official_coins: parse_or("OFFICIAL_COINS", 170, &mut invalid),
+            verify_seed2: parse_or("VERIFY_SEED2", false, &mut invalid),
+            leaderboard_ban_threshold: parse_or("LEADERBOARD_BAN_THRESHOLD", 3, &mut invalid),
+            leaderboard_ban_window: parse_or("LEADERBOARD_BAN_WINDOW", 7, &mut invalid),
+            auth_cache_size: parse_or("AUTH_CACHE_SIZE", 10000, &mut invalid),
+            auth_cache_ttl: parse_or("AUTH_CACHE_TTL", 600, &mut invalid),
+            mailer: var_or("MAILER", "smtp"),
+            mail_dir: var_or("MAIL_DIR", "./data/mail").into(),
+            smtp_from: var_or("SMTP_FROM", "Dashium <noreply@localhost>"),
+            smtp_host: var_or("SMTP_HOST", "localhost"),
+            smtp_port: parse_or("SMTP_PORT", 25, &mut invalid),
+            smtp_tls: var_or("SMTP_TLS", "none"),
+            smtp_username: var_or("SMTP_USERNAME", ""),
+            smtp_password: var_or("SMTP_PASSWORD", ""),
+            password_reset_ttl: parse_or("PASSWORD_RESET_TTL", 3600, &mut invalid),
+            account_activation: parse_or("ACCOUNT_ACTIVATION", false, &mut invalid),
+            activation_link_ttl: parse_or("ACTIVATION_LINK_TTL", 86400, &mut invalid),
+            secret_key: var_or("SECRET_KEY", ""),
+            invalid,
+        }
+    }
+
+    /// Checks for settings that can't work together, so the server refuses to start with them.
+    pub fn validate(&self) -> Result<()> {
+        if !self.invalid.is_empty() {
+            bail!("Invalid value for {}", self.invalid.join(", "));
+        }
+
+        if self.rotation_hour >= 24 {
+            bail!("ROTATION_HOUR must be between 0 and 23");
+        }
+
+        if self.account_activation && self.secret_key.is_empty() {
+            bail!("ACCOUNT_ACTIVATION requires a SECRET_KEY to sign activation links");
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the global server config.
+pub fn config() -> &'static Config {
+    &CONFIG
+}
+
+fn var_or(key: &str, default: &str) -> String {
+    env::var(key).unwrap_or_else(|_| default.to_string())
+}
+
+/// Parses a setting, or returns `default` if it isn't set. Values that don't parse are added to
+/// `invalid`, so the server refuses to start instead of silently using the default.
+fn parse_or<T: FromStr>(key: &'static str, default: T, invalid: &mut Vec<&'static str>) -> T {
+    let Ok(value) = env::var(key) else {
+        return default;
+    };
+
+    value.parse().unwrap_or_else(|_| {
+        invalid.push(key);
+        default
+    })
+}
+
+/// Parses a `min-max` range, like [`parse_or`]. Ranges with `min` above `max` are invalid.
+fn range_or(key: &'static str, default: (u32, u32), invalid: &mut Vec<&'static str>) -> (u32, u32) {
+    let Ok(range) = env::var(key) else {
+        return default;
+    };
+
+    let parsed = range
+        .split_once('-')
+        .and_then(|(min, max)| Some((min.trim().parse().ok()?, max.trim().parse().ok()?)));
+
+    match parsed {
+        Some((min, max)) if min <= max => (min, max),
+        _ => {
+            invalid.push(key);
+            default
+        }
+    }
+}
+
+/// Parses a comma separated list of IPs, like [`parse_or`].
+fn proxies_or_empty(key: &'static str, invalid: &mut Vec<&'static str>) -> Vec<IpAddr> {
+    let proxies: Result<Vec<IpAddr>, _> = var_or(key, "")
+        .split(',')
+        .map(str::trim)
+        .filter(|ip| !ip.is_empty())
+        .map(str::parse)
+        .collect();
+
+    proxies.unwrap_or_else(|_| {
+        invalid.push(key);
+        Vec::new()
+    })
+}
@@ -7,7 +7,6 @@ use crate::{AppError, GDResponse, models::Comment};
 #[derive(Deserialize, Serialize, Debug)]
 pub struct getGJComments21 {
     accountID: i32,
-    gjp2: String,
     gameVersion: i16,
     binaryVersion: i16,
     #[serde(default)]
@@ -11,7 +11,6 @@ use crate::{
 pub struct getGJCommentHistory {
     accountID: i32,
     userID: i32,
-    gjp2: String,
     page: i32,
     mode: u8,
     secret: String,
@@ -32,16 +31,10 @@ pub async fn getGJCommentHistory(
             .await?;
 
     match comment_setting {
-        1 => {
-            if target_id != user_id && !Friendship::exists(&pool, user_id, target_id).await? {
-                return Ok("-1".to_string());
-            }
-        }
-        2 => {
-            if target_id != user_id {
-                return Ok("-1".to_string());
-            }
+        1 if target_id != user_id && !Friendship::exists(&pool, user_id, target_id).await? => {
+            return Ok("-1".to_string());
         }
+        2 if target_id != user_id => return Ok("-1".to_string()),
         _ => (),
     }
 
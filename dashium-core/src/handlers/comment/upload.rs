@@ -1,17 +1,17 @@
-use axum::{Form, extract::State};
+use axum::extract::State;
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
 
 use crate::{
     AppError,
-    models::Comment,
-    util::{base64_decode, verify_gjp2},
+    auth::AuthForm,
+    models::{Comment, Restriction, RestrictionKind},
+    util::base64_decode,
 };
 
 #[derive(Deserialize, Serialize, Debug)]
 pub struct uploadGJComment21 {
     accountID: i32,
-    gjp2: String,
     secret: String,
     comment: String,
     levelID: i32,
@@ -22,19 +22,22 @@ pub struct uploadGJComment21 {
 
 pub async fn uploadGJComment21(
     State(pool): State<PgPool>,
-    Form(form): Form<uploadGJComment21>,
+    AuthForm(auth, form): AuthForm<uploadGJComment21>,
 ) -> Result<String, AppError> {
     let user_id = form.accountID;
-    let gjp2 = &form.gjp2;
     let level_id = form.levelID;
     let username = &form.userName;
     let comment = &String::from_utf8(base64_decode(&form.comment)?)?;
     let percent = form.percent;
 
-    if !verify_gjp2(&pool, user_id, gjp2).await? {
+    if !auth.verify(&pool).await? {
         return Ok("-1".to_string());
     }
 
+    if let Some(ban) = Restriction::get_active(&pool, user_id, RestrictionKind::Comment).await? {
+        return Ok(ban.comment_ban_response());
+    }
+
     let comment_id = Comment::upload(&pool, user_id, username, level_id, comment, percent).await?;
     Ok(comment_id.to_string())
 }
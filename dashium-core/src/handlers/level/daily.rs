@@ -1,15 +1,13 @@
 use axum::{Form, extract::State};
-
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
 
 use crate::{
     AppError,
-    util::{
-        base64_decode, base64_encode, cyclic_xor, salt_and_sha1, time_until_midnight,
-        time_until_monday,
-    },
+    models::{Event, ScheduleKind, ScheduledLevel},
+    services::scheduler,
+    util::{base64_encode, cyclic_xor, decode_chk, salt_and_sha1},
 };
 
 #[derive(Deserialize, Serialize, Debug)]
@@ -24,74 +22,43 @@ pub async fn getGJDailyLevel(
     State(pool): State<PgPool>,
     Form(form): Form<getGJDailyLevel>,
 ) -> Result<String, AppError> {
-    let daily_type = form.daily_type;
-    let chk = &cyclic_xor(&base64_decode(&form.chk[5..])?, b"59182")?;
-    let now = Utc::now();
-    let time_left;
+    let Some(chk) = decode_chk(&form.chk, b"59182") else {
+        return Ok("-1".to_string());
+    };
 
-    let daily_id = match daily_type {
-        0 => {
-            time_left = time_until_monday();
-            sqlx::query_scalar!(
-                r#"
-                SELECT id
-                FROM daily_levels
-                WHERE created_at < $1
-                ORDER BY created_at DESC
-                LIMIT 1
-                "#,
-                now
-            )
-            .fetch_one(&pool)
-            .await?
-        }
-        1 => {
-            time_left = time_until_midnight();
-            sqlx::query_scalar!(
-                r#"
-                SELECT id + 100000
-                FROM weekly_demons
-                WHERE created_at < $1
-                ORDER BY created_at DESC
-                LIMIT 1
-                "#,
-                now
-            )
-            .fetch_one(&pool)
-            .await?
-            .unwrap_or(0)
-        }
-        2 => {
-            time_left = 10.to_string();
-            sqlx::query_scalar!(
-                r#"
-                SELECT id + 200000
-                FROM event_levels
-                WHERE created_at < $1
-                ORDER BY created_at DESC
-                LIMIT 1
-                "#,
-                now
-            )
-            .fetch_one(&pool)
-            .await?
-            .unwrap_or(0)
-        }
-        _ => return Ok("-1".to_string()),
+    let Some(kind) = ScheduleKind::from_type(form.daily_type) else {
+        return Ok("-1".to_string());
     };
 
-    let response = if daily_type == 2 {
-        let number = daily_id + 19;
-        let rewards = "7,500,1001,379";
+    let now = Utc::now();
+    let current = ScheduledLevel::current(&pool, kind).await?;
+    let daily_id = current
+        .as_ref()
+        .map_or(0, |scheduled| scheduled.id + kind.id_offset());
+
+    let (_, ends_at) = scheduler::rotation(&pool, kind, now).await?;
+    let time_left = (ends_at - now).num_seconds();
 
-        let list = format!("EVENT:{chk}:{number}:3:{rewards}");
-        let string = base64_encode(&cyclic_xor(list.as_bytes(), b"59182")?);
-        let hash = salt_and_sha1(&string, "pC26fpYaQCtg");
+    if kind != ScheduleKind::Event {
+        return Ok(format!("{daily_id}|{time_left}"));
+    }
 
-        format!("{daily_id}|{time_left}|EVENT{string}|{hash}")
-    } else {
-        format!("{daily_id}|{time_left}")
+    let event = match current.as_ref().and_then(|scheduled| scheduled.event_id) {
+        Some(event_id) => Event::get(&pool, event_id).await?,
+        None => None,
     };
 
+    // Event levels queued without an event definition give no rewards
+    let (number, rewards) = match event {
+        Some(event) => (event.number, event.rewards),
+        None => (daily_id, String::new()),
+    };
+
+    let list = format!("EVENT:{chk}:{number}:3:{rewards}");
+    let string = base64_encode(&cyclic_xor(list.as_bytes(), b"59182")?);
+    let hash = salt_and_sha1(&string, "pC26fpYaQCtg");
+
+    let response = format!("{daily_id}|{time_left}|EVENT{string}|{hash}");
+
     Ok(response)
 }
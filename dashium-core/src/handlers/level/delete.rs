@@ -1,30 +1,33 @@
-use axum::{Form, extract::State};
+use axum::extract::State;
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
 
-use crate::{AppError, models::Level, util::verify_gjp2};
+use crate::{
+    AppError, auth::AuthForm, models::Level, services::creator_points, storage::level_store,
+};
 
 #[derive(Deserialize, Serialize, Debug)]
 pub struct deleteGJLevelUser20 {
     accountID: i32,
-    gjp2: String,
     levelID: i32,
     secret: String,
 }
 
 pub async fn deleteGJLevelUser20(
     State(pool): State<PgPool>,
-    Form(form): Form<deleteGJLevelUser20>,
+    AuthForm(auth, form): AuthForm<deleteGJLevelUser20>,
 ) -> Result<String, AppError> {
     let user_id = form.accountID;
-    let gjp2 = &form.gjp2;
     let level_id = form.levelID;
 
-    if !verify_gjp2(&pool, user_id, gjp2).await? {
+    if !auth.verify(&pool).await? {
         return Ok("-1".to_string());
     }
 
-    Level::delete(&pool, user_id, level_id).await?;
+    if Level::delete(&pool, user_id, level_id).await? {
+        level_store().delete(level_id).await?;
+        creator_points::update(&pool, user_id).await?;
+    }
 
     Ok("1".to_string())
 }
@@ -1,112 +1,75 @@
 use crate::GDResponse;
+use crate::auth::AuthForm;
+use crate::client_ip::ClientIp;
 use crate::util::base64_encode;
-use crate::{AppError, models::Level, util::salt_and_sha1};
-use axum::{Form, extract::State};
-use chrono::Utc;
+use crate::{
+    AppError,
+    models::{Level, ScheduleKind, ScheduledLevel},
+    storage::level_store,
+    util::salt_and_sha1,
+};
+use axum::extract::State;
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
-use std::path::Path;
-use tokio::fs::File;
-use tokio::io::AsyncReadExt;
 
 #[derive(Deserialize, Serialize, Debug)]
 pub struct downloadGJLevel22 {
+    #[serde(default)]
     accountID: i32,
-    gjp2: String,
     levelID: i32,
     secret: String,
 }
 
 pub async fn downloadGJLevel22(
     State(pool): State<PgPool>,
-    Form(form): Form<downloadGJLevel22>,
+    ClientIp(ip): ClientIp,
+    AuthForm(auth, form): AuthForm<downloadGJLevel22>,
 ) -> Result<String, AppError> {
+    let user_id = form.accountID;
     let level_id = form.levelID;
     let mut daily = false;
     let mut daily_id = 0;
     let mut actual_level_id = 0;
-    let now = Utc::now();
 
-    let mut level = match level_id {
-        -1 => {
-            daily = true;
-            let row = sqlx::query!(
-                r#"
-                SELECT id, level_id
-                FROM daily_levels
-                WHERE created_at < $1
-                ORDER BY created_at DESC
-                "#,
-                now
-            )
-            .fetch_one(&pool)
-            .await?;
-
-            daily_id = row.id;
-            actual_level_id = row.level_id;
-
-            sqlx::query_as!(Level, "SELECT * FROM levels WHERE id = $1", row.level_id)
-                .fetch_one(&pool)
-                .await?
-        }
-        -2 => {
-            daily = true;
-            let row = sqlx::query!(
-                r#"
-                SELECT id + 100000 AS daily_id, level_id
-                FROM weekly_demons
-                WHERE created_at < $1
-                ORDER BY created_at DESC
-                "#,
-                now
-            )
-            .fetch_one(&pool)
-            .await?;
-
-            daily_id = row.daily_id.unwrap_or(0);
-            actual_level_id = row.level_id;
-
-            sqlx::query_as!(Level, "SELECT * FROM levels WHERE id = $1", row.level_id)
-                .fetch_one(&pool)
-                .await?
-        }
-        -3 => {
+    let schedule_kind = match level_id {
+        -1 => Some(ScheduleKind::Daily),
+        -2 => Some(ScheduleKind::Weekly),
+        -3 => Some(ScheduleKind::Event),
+        _ => None,
+    };
+
+    let mut level = match schedule_kind {
+        Some(kind) => {
+            let Some(scheduled) = ScheduledLevel::current(&pool, kind).await? else {
+                return Ok("-1".to_string());
+            };
+
             daily = true;
-            let row = sqlx::query!(
-                r#"
-                SELECT id + 200000 AS daily_id, level_id
-                FROM event_levels
-                WHERE created_at < $1
-                ORDER BY created_at DESC
-                "#,
-                now
-            )
-            .fetch_one(&pool)
-            .await?;
-
-            daily_id = row.daily_id.unwrap_or(0);
-            actual_level_id = row.level_id;
-
-            sqlx::query_as!(Level, "SELECT * FROM levels WHERE id = $1", row.level_id)
-                .fetch_one(&pool)
-                .await?
+            daily_id = scheduled.id + kind.id_offset();
+            actual_level_id = scheduled.level_id;
+
+            Level::get(&pool, scheduled.level_id).await?
         }
-        _ => Level::get(&pool, level_id).await?,
+        None => Level::get(&pool, level_id).await?,
     };
 
     level.description = base64_encode(&level.description);
 
-    let path = if daily {
-        format!("./data/levels/{actual_level_id}.level")
+    let downloader = if user_id != 0 && auth.verify(&pool).await? {
+        Some(user_id)
     } else {
-        format!("./data/levels/{level_id}.level")
+        None
     };
 
-    let path = Path::new(&path);
-    let mut file = File::open(path).await?;
-    let mut level_data = vec![];
-    file.read_to_end(&mut level_data).await?;
-    let level_data = String::from_utf8(level_data)?;
+    if Level::record_download(&pool, level.id, downloader, &ip.to_string()).await? {
+        level.downloads += 1;
+    }
+
+    let level_data = if daily {
+        level_store().get(actual_level_id).await?
+    } else {
+        level_store().get(level_id).await?
+    };
 
     let hash1 = generate_hash1(&level_data);
     let hash2 = generate_hash2(&level, daily_id);
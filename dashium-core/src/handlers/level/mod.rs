@@ -18,3 +18,9 @@ pub use crate::level::daily::getGJDailyLevel;
 
 mod update;
 pub use crate::level::update::updateGJDesc20;
+
+mod versions;
+pub use crate::level::versions::getGJLevelVersions;
+
+mod rollback;
+pub use crate::level::rollback::rollbackGJLevel;
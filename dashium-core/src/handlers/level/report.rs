@@ -1,24 +1,40 @@
-use axum::{Form, extract::State};
+use axum::extract::State;
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
 
 use crate::AppError;
+use crate::auth::AuthForm;
+use crate::client_ip::ClientIp;
+use crate::config::config;
+use crate::models::{Level, LevelReport};
 
 #[derive(Deserialize, Serialize, Debug)]
 pub struct reportGJLevel {
+    #[serde(default)]
+    accountID: i32,
     levelID: i32,
     secret: String,
 }
 
 pub async fn reportGJLevel(
     State(pool): State<PgPool>,
-    Form(form): Form<reportGJLevel>,
+    ClientIp(ip): ClientIp,
+    AuthForm(auth, form): AuthForm<reportGJLevel>,
 ) -> Result<String, AppError> {
+    let user_id = form.accountID;
     let level_id = form.levelID;
 
-    sqlx::query!("INSERT INTO level_reports (level_id) VALUES ($1)", level_id)
-        .execute(&pool)
-        .await?;
+    let reporter = if user_id != 0 && auth.verify(&pool).await? {
+        Some(user_id)
+    } else {
+        None
+    };
+
+    let reports = LevelReport::create(&pool, level_id, reporter, &ip.to_string()).await?;
+
+    if reports >= config().report_threshold {
+        Level::set_hidden(&pool, level_id, true).await?;
+    }
 
     Ok("1".to_string())
 }
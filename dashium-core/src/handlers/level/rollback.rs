@@ -0,0 +1,67 @@
+use axum::extract::State;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+use crate::{
+    AppError,
+    auth::AuthForm,
+    models::{Level, LevelVersion},
+    services::permissions::{self, Permission},
+    storage::level_store,
+};
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct rollbackGJLevel {
+    accountID: i32,
+    levelID: i32,
+    version: i32,
+    secret: String,
+}
+
+pub async fn rollbackGJLevel(
+    State(pool): State<PgPool>,
+    AuthForm(auth, form): AuthForm<rollbackGJLevel>,
+) -> Result<String, AppError> {
+    let user_id = form.accountID;
+    let level_id = form.levelID;
+    let version = form.version;
+
+    if !auth.verify(&pool).await? {
+        return Ok("-1".to_string());
+    }
+
+    let Some(level) = Level::find(&pool, level_id).await? else {
+        return Ok("-1".to_string());
+    };
+
+    if level.user_id != user_id && !permissions::has(&pool, user_id, Permission::EditLevels).await?
+    {
+        return Ok("-1".to_string());
+    }
+
+    let Some(level_version) = LevelVersion::get(&pool, level_id, version).await? else {
+        return Ok("-1".to_string());
+    };
+
+    let mut tx = pool.begin().await?;
+
+    // Keep the current level string so the rollback itself can be undone
+    let current_version = Level::lock_version(&mut tx, level_id).await?;
+    let current_level_string = level_store().get(level_id).await?;
+    LevelVersion::create(&mut tx, level_id, current_version, &current_level_string).await?;
+
+    sqlx::query!(
+        "UPDATE levels SET version = version + 1, updated_at = NOW() WHERE id = $1",
+        level_id
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    level_store()
+        .put(level_id, &level_version.level_string)
+        .await?;
+
+    Ok("1".to_string())
+}
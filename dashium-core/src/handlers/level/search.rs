@@ -1,10 +1,12 @@
-use axum::extract::{RawForm, State};
+use axum::extract::State;
 use serde::{Deserialize, Serialize};
 use sqlx::{PgPool, Postgres, QueryBuilder};
 
 use crate::{
     AppError, GDResponse,
-    models::Level,
+    auth::AuthForm,
+    config::config,
+    models::{Level, Song},
     util::{int_to_bool, is_numeric, salt_and_sha1},
 };
 
@@ -32,7 +34,6 @@ pub struct getGJLevels21 {
     gameVersion: i16,
     #[serde(default)]
     gauntlet: i32,
-    gjp2: String,
     #[serde(deserialize_with = "int_to_bool")]
     #[serde(default)]
     legendary: bool,
@@ -69,10 +70,8 @@ pub struct getGJLevels21 {
 
 pub async fn getGJLevels21(
     State(pool): State<PgPool>,
-    RawForm(form): RawForm,
+    AuthForm(auth, form): AuthForm<getGJLevels21>,
 ) -> Result<String, AppError> {
-    let form: getGJLevels21 = serde_html_form::from_bytes(&form)?;
-
     let user_id = form.accountID;
     let page = form.page;
     let search = &form.str;
@@ -100,14 +99,14 @@ pub async fn getGJLevels21(
             7 => {
                 sqlx::query_as!(
                     Level,
-                    "SELECT * FROM levels WHERE objects > 9999 ORDER BY created_at DESC"
+                    "SELECT * FROM levels WHERE objects > 9999 AND NOT hidden ORDER BY created_at DESC"
                 )
                 .fetch_all(&pool)
                 .await?
             }
             11 => sqlx::query_as!(
                 Level,
-                "SELECT * FROM levels WHERE rated = TRUE ORDER BY rated_at DESC, created_at DESC"
+                "SELECT * FROM levels WHERE rated = TRUE AND NOT hidden ORDER BY rated_at DESC, created_at DESC"
             )
             .fetch_all(&pool)
             .await?,
@@ -121,7 +120,7 @@ pub async fn getGJLevels21(
                     Level,
                     r#"
                 SELECT * FROM levels
-                WHERE user_id = ANY($1)
+                WHERE user_id = ANY($1) AND NOT hidden
                 ORDER BY created_at DESC
                 "#,
                     &user_ids
@@ -135,7 +134,7 @@ pub async fn getGJLevels21(
                     r#"
                     SELECT levels.*
                     FROM levels
-                    WHERE levels.user_id IN (
+                    WHERE NOT levels.hidden AND levels.user_id IN (
                         SELECT 
                         CASE 
                             WHEN friendships.user1 = $1 THEN friendships.user2
@@ -158,7 +157,8 @@ pub async fn getGJLevels21(
                     SELECT levels.*
                     FROM levels
                     JOIN daily_levels ON levels.id = daily_levels.level_id
-                    ORDER BY daily_levels.id DESC
+                    WHERE daily_levels.starts_at <= NOW()
+                    ORDER BY daily_levels.starts_at DESC
                     "#
                 )
                 .fetch_all(&pool)
@@ -171,7 +171,8 @@ pub async fn getGJLevels21(
                     SELECT levels.*
                     FROM levels
                     JOIN weekly_demons ON levels.id = weekly_demons.level_id
-                    ORDER BY weekly_demons.id DESC
+                    WHERE weekly_demons.starts_at <= NOW()
+                    ORDER BY weekly_demons.starts_at DESC
                     "#
                 )
                 .fetch_all(&pool)
@@ -184,7 +185,8 @@ pub async fn getGJLevels21(
                     SELECT levels.*
                     FROM levels
                     JOIN event_levels ON levels.id = event_levels.level_id
-                    ORDER BY event_levels.id DESC
+                    WHERE event_levels.starts_at <= NOW()
+                    ORDER BY event_levels.starts_at DESC
                     "#
                 )
                 .fetch_all(&pool)
@@ -212,7 +214,7 @@ pub async fn getGJLevels21(
                     r#"
                 SELECT *
                 FROM levels
-                WHERE id = ANY($1)
+                WHERE id = ANY($1) AND NOT hidden
                 "#,
                     &level_ids
                 )
@@ -226,6 +228,7 @@ pub async fn getGJLevels21(
                 SELECT DISTINCT levels.*
                 FROM levels
                 JOIN suggestions ON suggestions.level_id = levels.id
+                WHERE NOT levels.hidden
                 "#
                 )
                 .fetch_all(&pool)
@@ -233,7 +236,25 @@ pub async fn getGJLevels21(
             }
             _ => {
                 let mut query: QueryBuilder<Postgres> =
-                    QueryBuilder::new("SELECT * FROM levels WHERE 1 = 1");
+                    QueryBuilder::new("SELECT levels.* FROM levels");
+
+                // Downloads over the last `TRENDING_DAYS` days, levels without any aren't trending
+                if search_type == 3 {
+                    query.push(" JOIN (SELECT level_id, SUM(downloads) AS recent_downloads");
+                    query.push(" FROM level_daily_downloads WHERE day > CURRENT_DATE - ");
+                    query.push_bind(config().trending_days);
+                    query.push(" GROUP BY level_id) trending ON trending.level_id = levels.id");
+                }
+
+                query.push(" WHERE 1 = 1");
+
+                // Creators still see their own hidden levels, once they proved who they are
+                let own_levels =
+                    search_type == 5 && local && user_id != 0 && auth.verify(&pool).await?;
+
+                if !own_levels {
+                    query.push(" AND NOT hidden");
+                }
 
                 if coins {
                     query.push(" AND coins = TRUE");
@@ -357,7 +378,7 @@ pub async fn getGJLevels21(
                     2 => query.push(" ORDER BY likes DESC"),
                     3 => {
                         query.push(" AND created_at >= NOW() - INTERVAL '14 days'");
-                        query.push(" ORDER BY downloads DESC")
+                        query.push(" ORDER BY trending.recent_downloads DESC")
                     }
                     4 => query.push(" ORDER BY created_at DESC"),
                     _ => &mut query,
@@ -375,12 +396,12 @@ pub async fn getGJLevels21(
     let count = levels.len();
     let page_info = format!("{count}:{offset}:10");
 
-    let song_string = String::new();
+    let songs = Song::get_many(&pool, &song_ids(&levels)).await?;
 
     let response = [
         level_string(&levels),
         creator_string(&levels),
-        song_string,
+        song_string(&songs),
         page_info,
         generate_hash(&levels),
     ];
@@ -416,6 +437,36 @@ fn creator_string(levels: &Vec<Level>) -> String {
     creator_string
 }
 
+fn song_ids(levels: &[Level]) -> Vec<i32> {
+    let mut song_ids: Vec<i32> = levels
+        .iter()
+        .flat_map(|level| {
+            level
+                .song_ids
+                .split(',')
+                .filter_map(|s| s.trim().parse::<i32>().ok())
+                .chain([level.song_id])
+        })
+        .filter(|&song_id| song_id > 0)
+        .collect();
+
+    song_ids.sort_unstable();
+    song_ids.dedup();
+    song_ids
+}
+
+fn song_string(songs: &[Song]) -> String {
+    let mut song_string = String::new();
+
+    for song in songs {
+        song_string.push_str(&song.to_gd());
+        song_string.push_str("~:~");
+    }
+
+    song_string.truncate(song_string.len().saturating_sub(3));
+    song_string
+}
+
 pub fn generate_hash(levels: &Vec<Level>) -> String {
     let mut hash = String::new();
 
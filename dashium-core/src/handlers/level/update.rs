@@ -1,31 +1,26 @@
-use axum::{Form, extract::State};
+use axum::extract::State;
 use serde::Deserialize;
 use sqlx::PgPool;
 
-use crate::{
-    AppError,
-    util::{base64_decode, verify_gjp2},
-};
+use crate::{AppError, auth::AuthForm, util::base64_decode};
 
 #[derive(Deserialize)]
 pub struct updateGJDesc20 {
     accountID: i32,
-    gjp2: String,
     levelDesc: String,
     levelID: i32,
 }
 
 pub async fn updateGJDesc20(
     State(pool): State<PgPool>,
-    Form(form): Form<updateGJDesc20>,
+    AuthForm(auth, form): AuthForm<updateGJDesc20>,
 ) -> Result<String, AppError> {
     let user_id = form.accountID;
-    let gjp2 = &form.gjp2;
     let level_id = form.levelID;
 
     let description = String::from_utf8(base64_decode(&form.levelDesc)?)?;
 
-    if !verify_gjp2(&pool, user_id, gjp2).await? {
+    if !auth.verify(&pool).await? {
         return Ok("-1".to_string());
     }
 
@@ -1,13 +1,14 @@
-use axum::{Form, extract::State};
+use axum::extract::State;
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
-use std::path::Path;
-use tokio::fs::File;
-use tokio::io::AsyncWriteExt;
 
 use crate::{
     AppError,
-    util::{base64_decode, int_to_bool, verify_gjp2},
+    auth::AuthForm,
+    level_string::LevelString,
+    models::{Level, LevelVersion, Restriction, RestrictionKind},
+    storage::level_store,
+    util::{base64_decode, int_to_bool},
 };
 
 #[derive(Deserialize, Serialize, Debug)]
@@ -17,7 +18,6 @@ pub struct uploadGJLevel21 {
     binaryVersion: i16,
     coins: i16,
     gameVersion: i16,
-    gjp2: String,
     #[serde(deserialize_with = "int_to_bool")]
     ldm: bool,
     levelDesc: String,
@@ -46,17 +46,16 @@ pub struct uploadGJLevel21 {
 
 pub async fn uploadGJLevel21(
     State(pool): State<PgPool>,
-    Form(form): Form<uploadGJLevel21>,
+    AuthForm(auth, form): AuthForm<uploadGJLevel21>,
 ) -> Result<String, AppError> {
     let user_id = form.accountID;
-    let gjp2 = &form.gjp2;
     let official_song = form.audioTrack;
     let binary_version = form.binaryVersion;
     let coins = form.coins;
     let game_version = form.gameVersion;
-    let ldm = form.ldm;
+    let mut ldm = form.ldm;
     let level_info = &form.levelInfo;
-    let length = form.levelLength;
+    let mut length = form.levelLength;
     let level_name = &form.levelName;
     let level_string = &form.levelString;
     let version = form.levelVersion;
@@ -74,10 +73,117 @@ pub async fn uploadGJLevel21(
 
     let description = String::from_utf8(base64_decode(&form.levelDesc)?)?;
 
-    if !verify_gjp2(&pool, user_id, gjp2).await? {
+    if !auth.verify(&pool).await? {
         return Ok("-1".to_string());
     }
 
+    if Restriction::is_restricted(&pool, user_id, RestrictionKind::Creator).await? {
+        return Ok("-1".to_string());
+    }
+
+    let decoded = {
+        let level_string = level_string.clone();
+        tokio::task::spawn_blocking(move || LevelString::decode(&level_string)).await?
+    };
+
+    let Ok(decoded) = decoded else {
+        return Ok("-1".to_string());
+    };
+
+    if !decoded.matches_counts(objects as usize, coins as usize) {
+        return Ok("-1".to_string());
+    }
+
+    let platformer = decoded.settings.platformer;
+    ldm = ldm && decoded.has_ldm();
+
+    if platformer {
+        length = 5;
+    }
+
+    let existing = match form.levelID {
+        0 => None,
+        level_id => match Level::find(&pool, level_id).await? {
+            Some(level) => Some(level),
+            None => return Ok("-1".to_string()),
+        },
+    };
+
+    if let Some(level) = existing {
+        if level.user_id != user_id {
+            return Ok("-1".to_string());
+        }
+
+        let mut tx = pool.begin().await?;
+
+        let old_version = Level::lock_version(&mut tx, level.id).await?;
+        let old_level_string = level_store().get(level.id).await?;
+        LevelVersion::create(&mut tx, level.id, old_version, &old_level_string).await?;
+
+        sqlx::query!(
+            r#"
+            UPDATE levels
+            SET
+                official_song = $1,
+                binary_version = $2,
+                coins = $3,
+                game_version = $4,
+                ldm = $5,
+                description = $6,
+                level_info = $7,
+                length = $8,
+                level_name = $9,
+                objects = $10,
+                original = $11,
+                password = $12,
+                requested_stars = $13,
+                song_id = $14,
+                two_player = $15,
+                unlisted = $16,
+                username = $17,
+                wt = $18,
+                wt2 = $19,
+                extra_string = $20,
+                platformer = $21,
+                version = version + 1,
+                updated_at = NOW()
+            WHERE id = $22
+            "#,
+            official_song,
+            binary_version,
+            coins,
+            game_version,
+            ldm,
+            description,
+            level_info,
+            length,
+            level_name,
+            objects,
+            original,
+            password,
+            requested_stars,
+            song_id,
+            two_player,
+            unlisted,
+            username,
+            wt,
+            wt2,
+            extra_string,
+            platformer,
+            level.id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        // The level string is only replaced once the new version is saved, and the old one can
+        // be restored from the version history if writing it fails
+        level_store().put(level.id, level_string).await?;
+
+        return Ok(level.id.to_string());
+    }
+
     let level_id: i32 = sqlx::query_scalar!(
         r#"
         INSERT INTO levels (
@@ -102,7 +208,8 @@ pub async fn uploadGJLevel21(
             username,
             wt,
             wt2,
-            extra_string
+            extra_string,
+            platformer
         )
         VALUES (
             $1,
@@ -126,7 +233,8 @@ pub async fn uploadGJLevel21(
             $19,
             $20,
             $21,
-            $22
+            $22,
+            $23
         )
         RETURNING id
         "#,
@@ -151,16 +259,13 @@ pub async fn uploadGJLevel21(
         username,
         wt,
         wt2,
-        extra_string
+        extra_string,
+        platformer
     )
     .fetch_one(&pool)
     .await?;
 
-    let path = format!("./data/levels/{level_id}.level");
-    let path = Path::new(&path);
-    let mut file = File::create(path).await?;
-    file.write_all(level_string.as_bytes()).await?;
-    file.flush().await?;
+    level_store().put(level_id, level_string).await?;
 
     Ok(level_id.to_string())
 }
@@ -0,0 +1,55 @@
+use axum::extract::State;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+use crate::{
+    AppError, GDResponse,
+    auth::AuthForm,
+    models::{Level, LevelVersion},
+    services::permissions::{self, Permission},
+};
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct getGJLevelVersions {
+    accountID: i32,
+    levelID: i32,
+    secret: String,
+}
+
+pub async fn getGJLevelVersions(
+    State(pool): State<PgPool>,
+    AuthForm(auth, form): AuthForm<getGJLevelVersions>,
+) -> Result<String, AppError> {
+    let user_id = form.accountID;
+    let level_id = form.levelID;
+
+    if !auth.verify(&pool).await? {
+        return Ok("-1".to_string());
+    }
+
+    let Some(level) = Level::find(&pool, level_id).await? else {
+        return Ok("-1".to_string());
+    };
+
+    if level.user_id != user_id && !permissions::has(&pool, user_id, Permission::EditLevels).await?
+    {
+        return Ok("-1".to_string());
+    }
+
+    let level_versions = LevelVersion::get_all(&pool, level_id).await?;
+
+    if level_versions.is_empty() {
+        return Ok("-2".to_string());
+    }
+
+    let mut response = String::new();
+
+    for level_version in &level_versions {
+        response.push_str(&level_version.to_gd());
+        response.push('|');
+    }
+
+    response.pop();
+
+    Ok(response)
+}
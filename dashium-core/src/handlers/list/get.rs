@@ -11,7 +11,6 @@ pub struct getGJLevelLists {
     page: i32,
     #[serde(rename = "type")]
     search_type: u8,
-    gjp2: String,
     secret: String,
 }
 
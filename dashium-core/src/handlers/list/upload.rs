@@ -1,8 +1,8 @@
-use axum::{Form, extract::State};
+use axum::extract::State;
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
 
-use crate::{AppError, models::User, util::verify_gjp2};
+use crate::{AppError, auth::AuthForm, models::User};
 
 #[derive(Deserialize, Serialize, Debug)]
 pub struct uploadGJLevelList {
@@ -15,16 +15,14 @@ pub struct uploadGJLevelList {
     listVersion: String,
     original: i32,
     unlisted: i16,
-    gjp2: String,
     secret: String,
 }
 
 pub async fn uploadGJLevelList(
     State(pool): State<PgPool>,
-    Form(form): Form<uploadGJLevelList>,
+    AuthForm(auth, form): AuthForm<uploadGJLevelList>,
 ) -> Result<String, AppError> {
     let user_id = form.accountID;
-    let gjp2 = &form.gjp2;
     let list_name = &form.listName;
     let description = &form.listDesc;
     let original = form.original;
@@ -32,7 +30,7 @@ pub async fn uploadGJLevelList(
     let difficulty = form.difficulty;
     let levels = &form.listLevels;
 
-    if !verify_gjp2(&pool, user_id, gjp2).await? {
+    if !auth.verify(&pool).await? {
         return Ok("-1".to_string());
     }
 
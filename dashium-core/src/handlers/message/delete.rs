@@ -1,15 +1,14 @@
-use axum::{Form, extract::State};
+use axum::extract::State;
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
 
 use crate::AppError;
+use crate::auth::AuthForm;
 use crate::models::Message;
-use crate::util::verify_gjp2;
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct deleteGJMessages20 {
     accountID: i32,
-    gjp2: String,
     secret: String,
     #[serde(default)]
     messages: String,
@@ -21,15 +20,14 @@ pub struct deleteGJMessages20 {
 
 pub async fn deleteGJMessages20(
     State(pool): State<PgPool>,
-    Form(form): Form<deleteGJMessages20>,
+    AuthForm(auth, form): AuthForm<deleteGJMessages20>,
 ) -> Result<String, AppError> {
     let user_id = form.accountID;
-    let gjp2 = &form.gjp2;
     let message_id = form.messageID;
     let message_ids = form.messages;
     let is_sender = form.isSender;
 
-    if !verify_gjp2(&pool, user_id, gjp2).await? {
+    if !auth.verify(&pool).await? {
         return Ok("1".to_string());
     }
 
@@ -1,26 +1,24 @@
-use axum::{Form, extract::State};
+use axum::extract::State;
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
 
-use crate::{AppError, GDResponse, models::Message, util::verify_gjp2};
+use crate::{AppError, GDResponse, auth::AuthForm, models::Message};
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct downloadGJMessage20 {
     accountID: i32,
-    gjp2: String,
     messageID: i32,
     secret: String,
 }
 
 pub async fn downloadGJMessage20(
     State(pool): State<PgPool>,
-    Form(form): Form<downloadGJMessage20>,
+    AuthForm(auth, form): AuthForm<downloadGJMessage20>,
 ) -> Result<String, AppError> {
     let user_id = form.accountID;
     let message_id = form.messageID;
-    let gjp2 = &form.gjp2;
 
-    if !verify_gjp2(&pool, user_id, gjp2).await? {
+    if !auth.verify(&pool).await? {
         return Ok("-1".to_string());
     }
 
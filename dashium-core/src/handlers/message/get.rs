@@ -1,15 +1,14 @@
-use axum::{Form, extract::State};
+use axum::extract::State;
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
 
+use crate::auth::AuthForm;
 use crate::models::Message;
-use crate::util::verify_gjp2;
 use crate::{AppError, GDResponse};
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct getGJMessages20 {
     accountID: i32,
-    gjp2: String,
     page: i32,
     secret: String,
 
@@ -19,14 +18,13 @@ pub struct getGJMessages20 {
 
 pub async fn getGJMessages20(
     State(pool): State<PgPool>,
-    Form(form): Form<getGJMessages20>,
+    AuthForm(auth, form): AuthForm<getGJMessages20>,
 ) -> Result<String, AppError> {
     let user_id = form.accountID;
-    let gjp2 = &form.gjp2;
     let page = form.page;
     let get_sent = form.getSent;
 
-    if !verify_gjp2(&pool, user_id, gjp2).await? {
+    if !auth.verify(&pool).await? {
         return Ok("-1".to_string());
     }
 
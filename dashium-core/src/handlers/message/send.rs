@@ -1,16 +1,15 @@
-use axum::{Form, extract::State};
+use axum::extract::State;
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
 
 use crate::AppError;
+use crate::auth::AuthForm;
 use crate::models::Message;
-use crate::models::{Block, Friendship};
-use crate::util::verify_gjp2;
+use crate::models::{Block, Friendship, Restriction, RestrictionKind};
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct uploadGJMessage20 {
     accountID: i32,
-    gjp2: String,
     toAccountID: i32,
     subject: String,
     body: String,
@@ -19,15 +18,18 @@ pub struct uploadGJMessage20 {
 
 pub async fn uploadGJMessage20(
     State(pool): State<PgPool>,
-    Form(form): Form<uploadGJMessage20>,
+    AuthForm(auth, form): AuthForm<uploadGJMessage20>,
 ) -> Result<String, AppError> {
     let user_id = form.accountID;
-    let gjp2 = &form.gjp2;
     let subject = &form.subject;
     let body = &form.body;
     let target_id = form.toAccountID;
 
-    if !verify_gjp2(&pool, user_id, gjp2).await? {
+    if !auth.verify(&pool).await? {
+        return Ok("-1".to_string());
+    }
+
+    if Restriction::is_restricted(&pool, user_id, RestrictionKind::Message).await? {
         return Ok("-1".to_string());
     }
 
@@ -41,10 +43,8 @@ pub async fn uploadGJMessage20(
             .await?;
 
     match message_setting {
-        1 => {
-            if !Friendship::exists(&pool, user_id, target_id).await? {
-                return Ok("-1".to_string());
-            }
+        1 if !Friendship::exists(&pool, user_id, target_id).await? => {
+            return Ok("-1".to_string());
         }
         2 => return Ok("-1".to_string()),
         _ => (),
@@ -1,8 +1,10 @@
 use crate::AppError;
-use crate::util::verify_gjp2;
-use axum::{Form, extract::State};
+use crate::auth::AuthForm;
+use crate::models::ItemLike;
+use crate::util::verify_chk;
+use axum::extract::State;
 use serde::{Deserialize, Serialize};
-use sqlx::{PgPool, Postgres, query_builder::QueryBuilder};
+use sqlx::PgPool;
 
 #[derive(Deserialize, Serialize, Debug)]
 pub struct likeGJItem211 {
@@ -10,50 +12,54 @@ pub struct likeGJItem211 {
     secret: String,
 
     #[serde(rename = "type")]
-    item_type: u8,
+    item_type: i16,
     chk: String,
     itemID: i32,
-    gjp2: String,
     like: u8,
+
+    #[serde(default)]
+    special: String,
+    #[serde(default)]
+    rs: String,
+    #[serde(default)]
+    udid: String,
+    #[serde(default)]
+    uuid: String,
 }
 
 pub async fn likeGJItem211(
     State(pool): State<PgPool>,
-    Form(form): Form<likeGJItem211>,
+    AuthForm(auth, form): AuthForm<likeGJItem211>,
 ) -> Result<String, AppError> {
     let user_id = form.accountID;
-    let gjp2 = &form.gjp2;
     let item_id = form.itemID;
     let like = form.like;
     let item_type = form.item_type;
 
-    if !verify_gjp2(&pool, user_id, gjp2).await? {
+    if !auth.verify(&pool).await? {
+        return Ok("1".to_string());
+    }
+
+    if !(1..=4).contains(&item_type) || like > 1 {
         return Ok("1".to_string());
     }
 
-    let mut query: QueryBuilder<Postgres> = QueryBuilder::new("UPDATE");
-
-    match item_type {
-        1 => query.push(" levels SET"),
-        2 => query.push(" comments SET"),
-        3 => query.push(" posts SET"),
-        4 => query.push(" lists SET"),
-        _ => return Ok("1".to_string()),
-    };
-
-    match like {
-        0 => {
-            query.push(" likes = likes - 1 WHERE id = ");
-            query.push_bind(item_id);
-        }
-        1 => {
-            query.push(" likes = likes + 1 WHERE id = ");
-            query.push_bind(item_id);
-        }
-        _ => return Ok("1".to_string()),
-    };
-
-    query.build().execute(&pool).await.unwrap();
+    let values = [
+        form.special.clone(),
+        item_id.to_string(),
+        like.to_string(),
+        item_type.to_string(),
+        form.rs.clone(),
+        user_id.to_string(),
+        form.udid.clone(),
+        form.uuid.clone(),
+    ];
+
+    if !verify_chk(&form.chk, &values, "ysg6pUrtjn0J", b"58281") {
+        return Ok("-1".to_string());
+    }
+
+    ItemLike::vote(&pool, user_id, item_type, item_id, like == 1).await?;
 
     Ok("1".to_string())
 }
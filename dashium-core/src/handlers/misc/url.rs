@@ -1,3 +1,5 @@
+use crate::config::config;
+
 pub async fn getAccountURL() -> &'static str {
-    "http://127.0.0.1:2207"
+    &config().server_url
 }
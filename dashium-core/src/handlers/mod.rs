@@ -9,4 +9,5 @@ pub mod post;
 pub mod relationship;
 pub mod reward;
 pub mod score;
+pub mod song;
 pub mod user;
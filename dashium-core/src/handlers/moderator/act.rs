@@ -0,0 +1,60 @@
+use axum::extract::State;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+use crate::{
+    AppError,
+    auth::AuthForm,
+    models::{Level, LevelReport},
+    services::{
+        creator_points,
+        permissions::{self, Permission},
+    },
+    storage::level_store,
+};
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct actGJLevelReports {
+    accountID: i32,
+    levelID: i32,
+    /// 1 keeps the level hidden from searches, 2 deletes it.
+    action: u8,
+    secret: String,
+}
+
+/// Upholds the reports of a level.
+pub async fn actGJLevelReports(
+    State(pool): State<PgPool>,
+    AuthForm(auth, form): AuthForm<actGJLevelReports>,
+) -> Result<String, AppError> {
+    let user_id = form.accountID;
+    let level_id = form.levelID;
+
+    if !auth.verify(&pool).await? {
+        return Ok("-1".to_string());
+    }
+
+    if !permissions::has(&pool, user_id, Permission::ReviewReports).await? {
+        return Ok("-1".to_string());
+    }
+
+    match form.action {
+        1 => {
+            Level::set_hidden(&pool, level_id, true).await?;
+            LevelReport::clear(&pool, level_id).await?;
+        }
+        2 => {
+            let Some(level) = Level::find(&pool, level_id).await? else {
+                return Ok("-1".to_string());
+            };
+
+            if Level::delete(&pool, level.user_id, level_id).await? {
+                level_store().delete(level_id).await?;
+                creator_points::update(&pool, level.user_id).await?;
+            }
+        }
+        _ => return Ok("-1".to_string()),
+    }
+
+    Ok("1".to_string())
+}
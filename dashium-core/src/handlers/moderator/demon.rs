@@ -0,0 +1,44 @@
+use axum::extract::State;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+use crate::{
+    AppError,
+    auth::AuthForm,
+    models::Level,
+    services::permissions::{self, Permission},
+};
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct rateGJDemon21 {
+    accountID: i32,
+    levelID: i32,
+    rating: i16,
+    #[serde(default)]
+    mode: i16,
+    secret: String,
+}
+
+pub async fn rateGJDemon21(
+    State(pool): State<PgPool>,
+    AuthForm(auth, form): AuthForm<rateGJDemon21>,
+) -> Result<String, AppError> {
+    let user_id = form.accountID;
+    let level_id = form.levelID;
+    let rating = form.rating;
+    let mode = form.mode;
+
+    if !(1..=5).contains(&rating) {
+        return Ok("-1".to_string());
+    }
+
+    if !auth.verify(&pool).await? {
+        return Ok("-1".to_string());
+    }
+
+    if mode == 1 && permissions::has(&pool, user_id, Permission::Rate).await? {
+        Level::rate_demon(&pool, level_id, rating).await?;
+    }
+
+    Ok(level_id.to_string())
+}
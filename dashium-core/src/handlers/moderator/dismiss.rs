@@ -0,0 +1,39 @@
+use axum::extract::State;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+use crate::{
+    AppError,
+    auth::AuthForm,
+    models::{Level, LevelReport},
+    services::permissions::{self, Permission},
+};
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct dismissGJLevelReports {
+    accountID: i32,
+    levelID: i32,
+    secret: String,
+}
+
+/// Clears the reports of a level and makes it searchable again.
+pub async fn dismissGJLevelReports(
+    State(pool): State<PgPool>,
+    AuthForm(auth, form): AuthForm<dismissGJLevelReports>,
+) -> Result<String, AppError> {
+    let user_id = form.accountID;
+    let level_id = form.levelID;
+
+    if !auth.verify(&pool).await? {
+        return Ok("-1".to_string());
+    }
+
+    if !permissions::has(&pool, user_id, Permission::ReviewReports).await? {
+        return Ok("-1".to_string());
+    }
+
+    LevelReport::clear(&pool, level_id).await?;
+    Level::set_hidden(&pool, level_id, false).await?;
+
+    Ok("1".to_string())
+}
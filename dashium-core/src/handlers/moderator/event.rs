@@ -0,0 +1,49 @@
+use axum::extract::State;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+use crate::{
+    AppError,
+    auth::AuthForm,
+    models::Event,
+    services::permissions::{self, Permission},
+};
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct createGJEvent {
+    accountID: i32,
+    number: i32,
+    /// In seconds.
+    duration: i32,
+    /// Item types and amounts, as `type,amount,type,amount,...`.
+    #[serde(default)]
+    rewards: String,
+    secret: String,
+}
+
+/// Creates an event definition to schedule event levels with.
+pub async fn createGJEvent(
+    State(pool): State<PgPool>,
+    AuthForm(auth, form): AuthForm<createGJEvent>,
+) -> Result<String, AppError> {
+    let user_id = form.accountID;
+    let number = form.number;
+    let duration = form.duration;
+    let rewards = &form.rewards;
+
+    if number <= 0 || duration <= 0 || !Event::is_valid_rewards(rewards) {
+        return Ok("-1".to_string());
+    }
+
+    if !auth.verify(&pool).await? {
+        return Ok("-1".to_string());
+    }
+
+    if !permissions::has(&pool, user_id, Permission::SetDaily).await? {
+        return Ok("-1".to_string());
+    }
+
+    let event_id = Event::create(&pool, number, duration, rewards, user_id).await?;
+
+    Ok(event_id.to_string())
+}
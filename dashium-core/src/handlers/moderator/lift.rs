@@ -0,0 +1,39 @@
+use axum::extract::State;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+use crate::{
+    AppError,
+    auth::AuthForm,
+    models::Restriction,
+    services::permissions::{self, Permission},
+};
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct liftGJRestriction {
+    accountID: i32,
+    restrictionID: i32,
+    secret: String,
+}
+
+pub async fn liftGJRestriction(
+    State(pool): State<PgPool>,
+    AuthForm(auth, form): AuthForm<liftGJRestriction>,
+) -> Result<String, AppError> {
+    let user_id = form.accountID;
+    let restriction_id = form.restrictionID;
+
+    if !auth.verify(&pool).await? {
+        return Ok("-1".to_string());
+    }
+
+    if !permissions::has(&pool, user_id, Permission::Ban).await? {
+        return Ok("-1".to_string());
+    }
+
+    if !Restriction::lift(&pool, restriction_id).await? {
+        return Ok("-1".to_string());
+    }
+
+    Ok("1".to_string())
+}
@@ -3,3 +3,45 @@ pub use crate::moderator::request::requestUserAccess;
 
 mod suggest;
 pub use crate::moderator::suggest::suggestGJStars20;
+
+mod rate;
+pub use crate::moderator::rate::rateGJStars211;
+
+mod demon;
+pub use crate::moderator::demon::rateGJDemon21;
+
+mod save;
+pub use crate::moderator::save::getGJSaveDataInfo;
+
+mod submissions;
+pub use crate::moderator::submissions::getGJStatSubmissions;
+
+mod reports;
+pub use crate::moderator::reports::getGJLevelReports;
+
+mod dismiss;
+pub use crate::moderator::dismiss::dismissGJLevelReports;
+
+mod act;
+pub use crate::moderator::act::actGJLevelReports;
+
+mod restrictions;
+pub use crate::moderator::restrictions::getGJRestrictions;
+
+mod restrict;
+pub use crate::moderator::restrict::addGJRestriction;
+
+mod lift;
+pub use crate::moderator::lift::liftGJRestriction;
+
+mod schedule;
+pub use crate::moderator::schedule::scheduleGJDailyLevel;
+
+mod queue;
+pub use crate::moderator::queue::getGJDailyQueue;
+
+mod unschedule;
+pub use crate::moderator::unschedule::unscheduleGJDailyLevel;
+
+mod event;
+pub use crate::moderator::event::createGJEvent;
@@ -0,0 +1,54 @@
+use axum::extract::State;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+use crate::{
+    AppError, GDResponse,
+    auth::AuthForm,
+    models::{ScheduleKind, ScheduledLevel},
+    services::permissions::{self, Permission},
+};
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct getGJDailyQueue {
+    accountID: i32,
+    #[serde(rename = "type")]
+    daily_type: u8,
+    secret: String,
+}
+
+pub async fn getGJDailyQueue(
+    State(pool): State<PgPool>,
+    AuthForm(auth, form): AuthForm<getGJDailyQueue>,
+) -> Result<String, AppError> {
+    let user_id = form.accountID;
+
+    let Some(kind) = ScheduleKind::from_type(form.daily_type) else {
+        return Ok("-1".to_string());
+    };
+
+    if !auth.verify(&pool).await? {
+        return Ok("-1".to_string());
+    }
+
+    if !permissions::has(&pool, user_id, Permission::SetDaily).await? {
+        return Ok("-1".to_string());
+    }
+
+    let queue = ScheduledLevel::get_queue(&pool, kind).await?;
+
+    if queue.is_empty() {
+        return Ok("-2".to_string());
+    }
+
+    let mut response = String::new();
+
+    for scheduled in &queue {
+        response.push_str(&scheduled.to_gd());
+        response.push('|');
+    }
+
+    response.pop();
+
+    Ok(response)
+}
@@ -0,0 +1,32 @@
+use axum::extract::State;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+use crate::{AppError, auth::AuthForm};
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct rateGJStars211 {
+    accountID: i32,
+    levelID: i32,
+    stars: i16,
+    secret: String,
+}
+
+pub async fn rateGJStars211(
+    State(pool): State<PgPool>,
+    AuthForm(auth, form): AuthForm<rateGJStars211>,
+) -> Result<String, AppError> {
+    let stars = form.stars;
+
+    if !(1..=10).contains(&stars) {
+        return Ok("-1".to_string());
+    }
+
+    if !auth.verify(&pool).await? {
+        return Ok("-1".to_string());
+    }
+
+    // This is the difficulty vote every player gets, so it never rates the level, even when
+    // sent by a moderator. Levels are rated through `suggestGJStars20`. Votes are not tracked.
+    Ok("1".to_string())
+}
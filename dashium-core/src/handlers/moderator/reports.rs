@@ -0,0 +1,48 @@
+use axum::extract::State;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+use crate::{
+    AppError, GDResponse,
+    auth::AuthForm,
+    models::LevelReport,
+    services::permissions::{self, Permission},
+};
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct getGJLevelReports {
+    accountID: i32,
+    secret: String,
+}
+
+pub async fn getGJLevelReports(
+    State(pool): State<PgPool>,
+    AuthForm(auth, form): AuthForm<getGJLevelReports>,
+) -> Result<String, AppError> {
+    let user_id = form.accountID;
+
+    if !auth.verify(&pool).await? {
+        return Ok("-1".to_string());
+    }
+
+    if !permissions::has(&pool, user_id, Permission::ReviewReports).await? {
+        return Ok("-1".to_string());
+    }
+
+    let reports = LevelReport::get_all(&pool).await?;
+
+    if reports.is_empty() {
+        return Ok("-2".to_string());
+    }
+
+    let mut response = String::new();
+
+    for report in &reports {
+        response.push_str(&report.to_gd());
+        response.push('|');
+    }
+
+    response.pop();
+
+    Ok(response)
+}
@@ -0,0 +1,56 @@
+use axum::extract::State;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+use crate::{
+    AppError,
+    auth::AuthForm,
+    models::{Restriction, RestrictionKind},
+    services::permissions::{self, Permission},
+    util::base64_decode,
+};
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct addGJRestriction {
+    accountID: i32,
+    targetAccountID: i32,
+    /// One of `leaderboard`, `creator`, `comment`, `message` or `account`.
+    kind: String,
+    #[serde(default)]
+    reason: String,
+    /// In seconds, 0 for a permanent restriction.
+    #[serde(default)]
+    duration: i64,
+    secret: String,
+}
+
+pub async fn addGJRestriction(
+    State(pool): State<PgPool>,
+    AuthForm(auth, form): AuthForm<addGJRestriction>,
+) -> Result<String, AppError> {
+    let user_id = form.accountID;
+    let target_id = form.targetAccountID;
+    let reason = &String::from_utf8(base64_decode(&form.reason)?)?;
+    let duration = (form.duration > 0).then_some(form.duration);
+
+    let Some(kind) = RestrictionKind::parse(&form.kind) else {
+        return Ok("-1".to_string());
+    };
+
+    if form.duration < 0 || target_id == user_id {
+        return Ok("-1".to_string());
+    }
+
+    if !auth.verify(&pool).await? {
+        return Ok("-1".to_string());
+    }
+
+    if !permissions::has(&pool, user_id, Permission::Ban).await? {
+        return Ok("-1".to_string());
+    }
+
+    let restriction_id =
+        Restriction::create(&pool, target_id, kind, reason, Some(user_id), duration).await?;
+
+    Ok(restriction_id.to_string())
+}
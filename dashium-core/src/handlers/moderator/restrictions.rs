@@ -0,0 +1,50 @@
+use axum::extract::State;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+use crate::{
+    AppError, GDResponse,
+    auth::AuthForm,
+    models::Restriction,
+    services::permissions::{self, Permission},
+};
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct getGJRestrictions {
+    accountID: i32,
+    targetAccountID: i32,
+    secret: String,
+}
+
+pub async fn getGJRestrictions(
+    State(pool): State<PgPool>,
+    AuthForm(auth, form): AuthForm<getGJRestrictions>,
+) -> Result<String, AppError> {
+    let user_id = form.accountID;
+    let target_id = form.targetAccountID;
+
+    if !auth.verify(&pool).await? {
+        return Ok("-1".to_string());
+    }
+
+    if !permissions::has(&pool, user_id, Permission::Ban).await? {
+        return Ok("-1".to_string());
+    }
+
+    let restrictions = Restriction::get_all(&pool, target_id).await?;
+
+    if restrictions.is_empty() {
+        return Ok("-2".to_string());
+    }
+
+    let mut response = String::new();
+
+    for restriction in &restrictions {
+        response.push_str(&restriction.to_gd());
+        response.push('|');
+    }
+
+    response.pop();
+
+    Ok(response)
+}
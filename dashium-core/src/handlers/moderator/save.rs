@@ -0,0 +1,63 @@
+use axum::extract::State;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+use crate::{
+    AppError,
+    auth::AuthForm,
+    models::SaveBackup,
+    save_data::SaveData,
+    services::permissions::{self, Permission},
+};
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct getGJSaveDataInfo {
+    accountID: i32,
+    targetAccountID: i32,
+    secret: String,
+}
+
+/// Summarizes the latest save data backup of an account, for auditing.
+pub async fn getGJSaveDataInfo(
+    State(pool): State<PgPool>,
+    AuthForm(auth, form): AuthForm<getGJSaveDataInfo>,
+) -> Result<String, AppError> {
+    let user_id = form.accountID;
+    let target_id = form.targetAccountID;
+
+    if !auth.verify(&pool).await? {
+        return Ok("-1".to_string());
+    }
+
+    if !permissions::has(&pool, user_id, Permission::InspectAccounts).await? {
+        return Ok("-1".to_string());
+    }
+
+    let Some(save_data) = SaveBackup::load(&pool, target_id, None).await? else {
+        return Ok("-2".to_string());
+    };
+
+    let Ok(save_data) = tokio::task::spawn_blocking(move || SaveData::decode(&save_data)).await?
+    else {
+        return Ok("-3".to_string());
+    };
+
+    let stats = save_data.stats();
+    let icons = save_data.unlocked_icons();
+
+    let response = [
+        format!("1:{}", save_data.player_name().unwrap_or_default()),
+        format!("2:{}", stats.stars),
+        format!("3:{}", stats.moons),
+        format!("4:{}", stats.demons),
+        format!("5:{}", stats.diamonds),
+        format!("6:{}", stats.secret_coins),
+        format!("7:{}", stats.user_coins),
+        format!("8:{}", stats.online_levels),
+        format!("9:{}", save_data.completed_levels().len()),
+        format!("10:{}", icons.cubes.len()),
+        format!("11:{}", save_data.local_levels().len()),
+    ];
+
+    Ok(response.join(":"))
+}
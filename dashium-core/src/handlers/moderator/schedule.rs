@@ -0,0 +1,79 @@
+use axum::extract::State;
+use chrono::DateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+use crate::{
+    AppError,
+    auth::AuthForm,
+    models::{Event, ScheduleKind, ScheduledLevel},
+    services::permissions::{self, Permission},
+};
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct scheduleGJDailyLevel {
+    accountID: i32,
+    levelID: i32,
+    #[serde(rename = "type")]
+    daily_type: u8,
+    /// Unix timestamp, 0 to use the next free rotation.
+    #[serde(default)]
+    startsAt: i64,
+    /// Event definition of an event level, see `createGJEvent`.
+    #[serde(default)]
+    eventID: i32,
+    secret: String,
+}
+
+pub async fn scheduleGJDailyLevel(
+    State(pool): State<PgPool>,
+    AuthForm(auth, form): AuthForm<scheduleGJDailyLevel>,
+) -> Result<String, AppError> {
+    let user_id = form.accountID;
+    let level_id = form.levelID;
+    let event_id = form.eventID;
+
+    let Some(kind) = ScheduleKind::from_type(form.daily_type) else {
+        return Ok("-1".to_string());
+    };
+
+    let starts_at = match form.startsAt {
+        0 => None,
+        timestamp => match DateTime::from_timestamp(timestamp, 0) {
+            Some(starts_at) => Some(starts_at),
+            None => return Ok("-1".to_string()),
+        },
+    };
+
+    if !auth.verify(&pool).await? {
+        return Ok("-1".to_string());
+    }
+
+    if !permissions::has(&pool, user_id, Permission::SetDaily).await? {
+        return Ok("-1".to_string());
+    }
+
+    let Some(demon) = sqlx::query_scalar!("SELECT demon FROM levels WHERE id = $1", level_id)
+        .fetch_optional(&pool)
+        .await?
+    else {
+        return Ok("-1".to_string());
+    };
+
+    if kind == ScheduleKind::Weekly && !demon {
+        return Ok("-1".to_string());
+    }
+
+    if event_id != 0
+        && (kind != ScheduleKind::Event || Event::get(&pool, event_id).await?.is_none())
+    {
+        return Ok("-1".to_string());
+    }
+
+    let event_id = (event_id != 0).then_some(event_id);
+
+    let scheduled_id =
+        ScheduledLevel::queue(&pool, kind, level_id, starts_at, event_id, user_id).await?;
+
+    Ok(scheduled_id.to_string())
+}
@@ -0,0 +1,53 @@
+use axum::extract::State;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+use crate::{
+    AppError, GDResponse,
+    auth::AuthForm,
+    models::StatSubmission,
+    services::permissions::{self, Permission},
+    util::int_to_bool,
+};
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct getGJStatSubmissions {
+    accountID: i32,
+    targetAccountID: i32,
+    #[serde(default, deserialize_with = "int_to_bool")]
+    suspicious: bool,
+    secret: String,
+}
+
+pub async fn getGJStatSubmissions(
+    State(pool): State<PgPool>,
+    AuthForm(auth, form): AuthForm<getGJStatSubmissions>,
+) -> Result<String, AppError> {
+    let user_id = form.accountID;
+    let target_id = form.targetAccountID;
+
+    if !auth.verify(&pool).await? {
+        return Ok("-1".to_string());
+    }
+
+    if !permissions::has(&pool, user_id, Permission::InspectAccounts).await? {
+        return Ok("-1".to_string());
+    }
+
+    let submissions = StatSubmission::get_all(&pool, target_id, form.suspicious).await?;
+
+    if submissions.is_empty() {
+        return Ok("-2".to_string());
+    }
+
+    let mut response = String::new();
+
+    for submission in &submissions {
+        response.push_str(&submission.to_gd());
+        response.push('|');
+    }
+
+    response.pop();
+
+    Ok(response)
+}
@@ -1,13 +1,20 @@
-use axum::{Form, extract::State};
+use axum::extract::State;
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
 
-use crate::{AppError, util::verify_gjp2};
+use crate::{
+    AppError,
+    auth::AuthForm,
+    models::Level,
+    services::{
+        creator_points,
+        permissions::{self, Permission},
+    },
+};
 
 #[derive(Deserialize, Serialize, Debug)]
 pub struct suggestGJStars20 {
     accountID: i32,
-    gjp2: String,
     feature: i16,
     levelID: i32,
     stars: i16,
@@ -16,15 +23,14 @@ pub struct suggestGJStars20 {
 
 pub async fn suggestGJStars20(
     State(pool): State<PgPool>,
-    Form(form): Form<suggestGJStars20>,
+    AuthForm(auth, form): AuthForm<suggestGJStars20>,
 ) -> Result<String, AppError> {
     let user_id = form.accountID;
-    let gjp2 = &form.gjp2;
     let level_id = form.levelID;
     let feature = form.feature;
     let stars = form.stars;
 
-    let feature = match feature {
+    let feature_name = match feature {
         0 => "Star",
         1 => "Feature",
         2 => "Epic",
@@ -33,15 +39,29 @@ pub async fn suggestGJStars20(
         _ => return Ok("-1".to_string()),
     };
 
-    let mod_level = sqlx::query_scalar!("SELECT mod_level FROM users WHERE id = $1", user_id)
-        .fetch_one(&pool)
-        .await?;
+    if !(1..=10).contains(&stars) {
+        return Ok("-1".to_string());
+    }
 
-    if mod_level != 1 && mod_level != 2 {
+    if !auth.verify(&pool).await? {
         return Ok("-1".to_string());
     }
 
-    if !verify_gjp2(&pool, user_id, gjp2).await? {
+    // Moderators who can rate do so directly instead of suggesting the level
+    if permissions::has(&pool, user_id, Permission::Rate).await? {
+        let feature = permissions::has(&pool, user_id, Permission::Feature)
+            .await?
+            .then_some(feature);
+
+        let Some(creator_id) = Level::rate(&pool, level_id, stars, feature).await? else {
+            return Ok("-1".to_string());
+        };
+
+        creator_points::update(&pool, creator_id).await?;
+        return Ok("1".to_string());
+    }
+
+    if !permissions::has(&pool, user_id, Permission::Suggest).await? {
         return Ok("-1".to_string());
     }
 
@@ -49,7 +69,7 @@ pub async fn suggestGJStars20(
         "INSERT INTO suggestions (user_id, level_id, feature, stars) VALUES ($1, $2, $3, $4)",
         user_id,
         level_id,
-        feature,
+        feature_name,
         stars
     )
     .execute(&pool)
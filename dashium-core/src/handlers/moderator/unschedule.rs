@@ -0,0 +1,46 @@
+use axum::extract::State;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+use crate::{
+    AppError,
+    auth::AuthForm,
+    models::{ScheduleKind, ScheduledLevel},
+    services::permissions::{self, Permission},
+};
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct unscheduleGJDailyLevel {
+    accountID: i32,
+    /// The ID returned by `scheduleGJDailyLevel`.
+    scheduleID: i32,
+    #[serde(rename = "type")]
+    daily_type: u8,
+    secret: String,
+}
+
+pub async fn unscheduleGJDailyLevel(
+    State(pool): State<PgPool>,
+    AuthForm(auth, form): AuthForm<unscheduleGJDailyLevel>,
+) -> Result<String, AppError> {
+    let user_id = form.accountID;
+    let schedule_id = form.scheduleID;
+
+    let Some(kind) = ScheduleKind::from_type(form.daily_type) else {
+        return Ok("-1".to_string());
+    };
+
+    if !auth.verify(&pool).await? {
+        return Ok("-1".to_string());
+    }
+
+    if !permissions::has(&pool, user_id, Permission::SetDaily).await? {
+        return Ok("-1".to_string());
+    }
+
+    if !ScheduledLevel::unqueue(&pool, kind, schedule_id).await? {
+        return Ok("-1".to_string());
+    }
+
+    Ok("1".to_string())
+}
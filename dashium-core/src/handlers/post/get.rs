@@ -8,7 +8,6 @@ use crate::{AppError, GDResponse};
 #[derive(Serialize, Deserialize, Debug)]
 pub struct getGJAccountComments20 {
     accountID: Vec<i32>,
-    gjp2: String,
     page: i32,
     secret: String,
 }
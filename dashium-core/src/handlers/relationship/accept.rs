@@ -1,31 +1,29 @@
-use axum::{Form, extract::State};
+use axum::extract::State;
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
 
 use crate::{
     AppError,
+    auth::AuthForm,
     models::{Block, FriendRequest, Friendship},
-    util::verify_gjp2,
 };
 
 #[derive(Deserialize, Serialize, Debug)]
 pub struct acceptGJFriendRequest20 {
     accountID: i32,
     targetAccountID: i32,
-    gjp2: String,
     requestID: i32,
     secret: String,
 }
 
 pub async fn acceptGJFriendRequest20(
     State(pool): State<PgPool>,
-    Form(form): Form<acceptGJFriendRequest20>,
+    AuthForm(auth, form): AuthForm<acceptGJFriendRequest20>,
 ) -> Result<String, AppError> {
     let user_id = form.accountID;
     let target_id = form.targetAccountID;
-    let gjp2 = &form.gjp2;
 
-    if !verify_gjp2(&pool, user_id, gjp2).await? {
+    if !auth.verify(&pool).await? {
         return Ok("-1".to_string());
     }
 
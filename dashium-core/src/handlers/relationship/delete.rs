@@ -1,13 +1,12 @@
-use axum::{Form, extract::State};
+use axum::extract::State;
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
 
-use crate::{AppError, models::FriendRequest, util::verify_gjp2};
+use crate::{AppError, auth::AuthForm, models::FriendRequest};
 
 #[derive(Deserialize, Serialize, Debug)]
 pub struct deleteGJFriendRequests20 {
     accountID: i32,
-    gjp2: String,
     #[serde(default)]
     accounts: String,
     targetAccountID: i32,
@@ -17,15 +16,14 @@ pub struct deleteGJFriendRequests20 {
 
 pub async fn deleteGJFriendRequests20(
     State(pool): State<PgPool>,
-    Form(form): Form<deleteGJFriendRequests20>,
+    AuthForm(auth, form): AuthForm<deleteGJFriendRequests20>,
 ) -> Result<String, AppError> {
     let user_id = form.accountID;
-    let gjp2 = &form.gjp2;
     let target_id = form.targetAccountID;
     let target_ids = &form.accounts;
     let is_sender = form.isSender;
 
-    if !verify_gjp2(&pool, user_id, gjp2).await? {
+    if !auth.verify(&pool).await? {
         return Ok("1".to_string());
     }
 
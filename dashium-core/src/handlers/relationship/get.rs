@@ -1,13 +1,12 @@
-use axum::{Form, extract::State};
+use axum::extract::State;
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
 
-use crate::{AppError, GDResponse, models::FriendRequest, util::verify_gjp2};
+use crate::{AppError, GDResponse, auth::AuthForm, models::FriendRequest};
 
 #[derive(Deserialize, Serialize, Debug)]
 pub struct getGJFriendRequests20 {
     accountID: i32,
-    gjp2: String,
     page: i32,
     #[serde(default)]
     getSent: i16,
@@ -16,14 +15,13 @@ pub struct getGJFriendRequests20 {
 
 pub async fn getGJFriendRequests20(
     State(pool): State<PgPool>,
-    Form(form): Form<getGJFriendRequests20>,
+    AuthForm(auth, form): AuthForm<getGJFriendRequests20>,
 ) -> Result<String, AppError> {
     let user_id = form.accountID;
-    let gjp2 = &form.gjp2;
     let page = form.page;
     let get_sent = form.getSent;
 
-    if !verify_gjp2(&pool, user_id, gjp2).await? {
+    if !auth.verify(&pool).await? {
         return Ok("-1".to_string());
     }
 
@@ -1,17 +1,16 @@
-use axum::{Form, extract::State};
+use axum::extract::State;
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
 
 use crate::{
     AppError, GDResponse,
+    auth::AuthForm,
     models::{Block, Friendship, User},
-    util::verify_gjp2,
 };
 
 #[derive(Deserialize, Serialize, Debug)]
 pub struct getGJUserList20 {
     accountID: i32,
-    gjp2: String,
     #[serde(default)]
     #[serde(rename = "type")]
     list_type: u8,
@@ -20,13 +19,12 @@ pub struct getGJUserList20 {
 
 pub async fn getGJUserList20(
     State(pool): State<PgPool>,
-    Form(form): Form<getGJUserList20>,
+    AuthForm(auth, form): AuthForm<getGJUserList20>,
 ) -> Result<String, AppError> {
     let user_id = form.accountID;
-    let gjp2 = &form.gjp2;
     let list_type = form.list_type;
 
-    if !verify_gjp2(&pool, user_id, gjp2).await? {
+    if !auth.verify(&pool).await? {
         return Ok("-1".to_string());
     }
 
@@ -1,26 +1,24 @@
-use axum::{Form, extract::State};
+use axum::extract::State;
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
 
-use crate::{AppError, models::Friendship, util::verify_gjp2};
+use crate::{AppError, auth::AuthForm, models::Friendship};
 
 #[derive(Deserialize, Serialize, Debug)]
 pub struct removeGJFriend20 {
     accountID: i32,
-    gjp2: String,
     targetAccountID: i32,
     secret: String,
 }
 
 pub async fn removeGJFriend20(
     State(pool): State<PgPool>,
-    Form(form): Form<removeGJFriend20>,
+    AuthForm(auth, form): AuthForm<removeGJFriend20>,
 ) -> Result<String, AppError> {
     let user_id = form.accountID;
-    let gjp2 = &form.gjp2;
     let target_id = form.targetAccountID;
 
-    if !verify_gjp2(&pool, user_id, gjp2).await? {
+    if !auth.verify(&pool).await? {
         return Ok("-1".to_string());
     }
 
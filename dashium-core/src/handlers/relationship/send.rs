@@ -1,11 +1,11 @@
-use axum::{Form, extract::State};
+use axum::extract::State;
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
 
 use crate::{
     AppError,
+    auth::AuthForm,
     models::{Block, FriendRequest},
-    util::verify_gjp2,
 };
 
 #[derive(Deserialize, Serialize, Debug)]
@@ -14,7 +14,6 @@ pub struct uploadFriendRequest20 {
     binaryVersion: i16,
     comment: String,
     gameVersion: i16,
-    gjp2: String,
     secret: String,
     toAccountID: i32,
     udid: String,
@@ -23,14 +22,13 @@ pub struct uploadFriendRequest20 {
 
 pub async fn uploadFriendRequest20(
     State(pool): State<PgPool>,
-    Form(form): Form<uploadFriendRequest20>,
+    AuthForm(auth, form): AuthForm<uploadFriendRequest20>,
 ) -> Result<String, AppError> {
     let user_id = form.accountID;
-    let gjp2 = &form.gjp2;
     let target_id = form.toAccountID;
     let body = &form.comment;
 
-    if !verify_gjp2(&pool, user_id, gjp2).await? {
+    if !auth.verify(&pool).await? {
         return Ok("-1".to_string());
     }
 
@@ -1,13 +1,12 @@
-use axum::{Form, extract::State};
+use axum::extract::State;
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
 
-use crate::{AppError, models::Block, util::verify_gjp2};
+use crate::{AppError, auth::AuthForm, models::Block};
 
 #[derive(Deserialize, Serialize, Debug)]
 pub struct unblockGJUser20 {
     accountID: i32,
-    gjp2: String,
     targetAccountID: i32,
     gameVersion: i16,
     binaryVersion: i16,
@@ -18,13 +17,12 @@ pub struct unblockGJUser20 {
 
 pub async fn unblockGJUser20(
     State(pool): State<PgPool>,
-    Form(form): Form<unblockGJUser20>,
+    AuthForm(auth, form): AuthForm<unblockGJUser20>,
 ) -> Result<String, AppError> {
     let blocker_id = form.accountID;
-    let gjp2 = &form.gjp2;
     let blocked_id = form.targetAccountID;
 
-    if !verify_gjp2(&pool, blocker_id, gjp2).await? {
+    if !auth.verify(&pool).await? {
         return Ok("1".to_string());
     }
 
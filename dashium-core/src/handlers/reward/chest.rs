@@ -0,0 +1,99 @@
+use axum::extract::State;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+use crate::{
+    AppError,
+    auth::AuthForm,
+    models::{ChestKind, RewardChests},
+    util::{base64_encode, cyclic_xor, decode_chk, random_range, salt_and_sha1},
+};
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct getGJRewards {
+    #[serde(default)]
+    accountID: i32,
+    chk: String,
+    /// 0 only checks the chests, 1 opens the small one and 2 the large one.
+    #[serde(default)]
+    rewardType: u8,
+    secret: String,
+    udid: String,
+}
+
+pub async fn getGJRewards(
+    State(pool): State<PgPool>,
+    AuthForm(auth, form): AuthForm<getGJRewards>,
+) -> Result<String, AppError> {
+    let user_id = form.accountID;
+    let udid = &form.udid;
+    let reward_type = form.rewardType;
+    let Some(chk) = decode_chk(&form.chk, b"59182") else {
+        return Ok("-1".to_string());
+    };
+
+    if udid.len() > 64 {
+        return Ok("-1".to_string());
+    }
+
+    let opened = match reward_type {
+        0 => None,
+        1 => Some(ChestKind::Small),
+        2 => Some(ChestKind::Large),
+        _ => return Ok("-1".to_string()),
+    };
+
+    let owner = if user_id != 0 {
+        if !auth.verify(&pool).await? {
+            return Ok("-1".to_string());
+        }
+
+        Some(user_id)
+    } else {
+        None
+    };
+
+    let mut chests = RewardChests::get(&pool, owner, udid).await?;
+
+    if let Some(kind) = opened
+        && !chests.open(&pool, kind).await?
+    {
+        return Ok("-1".to_string());
+    }
+
+    let small_left = chests.time_left(ChestKind::Small);
+    let small_content = roll(ChestKind::Small);
+    let small_count = chests.count(ChestKind::Small);
+
+    let large_left = chests.time_left(ChestKind::Large);
+    let large_content = roll(ChestKind::Large);
+    let large_count = chests.count(ChestKind::Large);
+
+    let list = format!(
+        "chest:{user_id}:{chk}:{udid}:{user_id}:{small_left}:{small_content}:{small_count}:{large_left}:{large_content}:{large_count}:{reward_type}"
+    );
+
+    let list = base64_encode(&cyclic_xor(list.as_bytes(), b"59182")?);
+    let hash = salt_and_sha1(&list, "pC26fpYaQCtg");
+
+    let response = format!("chest{}|{}", &list, &hash);
+    Ok(response)
+}
+
+/// Rolls the contents of a chest, as `orbs,diamonds,shard,keys`.
+/// The shard is its item ID, from 1 to 5, or 0 when there is none.
+fn roll(kind: ChestKind) -> String {
+    let rewards = kind.rewards();
+
+    let orbs = random_range(rewards.orbs.0, rewards.orbs.1);
+    let diamonds = random_range(rewards.diamonds.0, rewards.diamonds.1);
+    let keys = random_range(rewards.keys.0, rewards.keys.1);
+
+    let shard = if random_range(1, 100) <= rewards.shard_chance {
+        random_range(1, 5)
+    } else {
+        0
+    };
+
+    format!("{orbs},{diamonds},{shard},{keys}")
+}
@@ -1,2 +1,8 @@
 mod quest;
 pub use crate::reward::quest::getGJChallenges;
+
+mod chest;
+pub use crate::reward::chest::getGJRewards;
+
+mod secret;
+pub use crate::reward::secret::getGJSecretReward;
@@ -1,30 +1,60 @@
-use axum::Form;
-
+use axum::extract::State;
+use chrono::Utc;
 use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
 
 use crate::{
-    AppError,
-    util::{base64_decode, base64_encode, cyclic_xor, salt_and_sha1, time_until_midnight},
+    AppError, GDResponse,
+    auth::AuthForm,
+    models::{IssuedQuest, ScheduleKind},
+    services::scheduler,
+    util::{base64_encode, cyclic_xor, decode_chk, salt_and_sha1},
 };
 
 #[derive(Deserialize, Serialize, Debug)]
 pub struct getGJChallenges {
+    #[serde(default)]
     accountID: i32,
     chk: String,
     secret: String,
     udid: String,
 }
 
-pub async fn getGJChallenges(Form(form): Form<getGJChallenges>) -> Result<String, AppError> {
-    let user_id = &form.accountID.to_string();
+pub async fn getGJChallenges(
+    State(pool): State<PgPool>,
+    AuthForm(auth, form): AuthForm<getGJChallenges>,
+) -> Result<String, AppError> {
+    let user_id = form.accountID;
     let udid = &form.udid;
-    let chk = &cyclic_xor(&base64_decode(&form.chk[5..])?, b"19847")?;
+    let Some(chk) = decode_chk(&form.chk, b"19847") else {
+        return Ok("-1".to_string());
+    };
+
+    if udid.len() > 64 {
+        return Ok("-1".to_string());
+    }
+
+    let owner = if user_id != 0 {
+        if !auth.verify(&pool).await? {
+            return Ok("-1".to_string());
+        }
+
+        Some(user_id)
+    } else {
+        None
+    };
+
+    // Quests rotate along with the daily level
+    let (day, _) = scheduler::period(ScheduleKind::Daily, Utc::now());
+    let time_left = scheduler::time_left(ScheduleKind::Daily);
+
+    let quests = IssuedQuest::get_or_issue(&pool, owner, udid, day.date_naive()).await?;
 
-    let time_left = time_until_midnight();
+    let [quest1, quest2, quest3] = quests.as_slice() else {
+        return Ok("-1".to_string());
+    };
 
-    let quest1 = "1,1,500,10,Orb Quest".to_string();
-    let quest2 = "2,2,6,20,Coin Quest".to_string();
-    let quest3 = "3,3,10,30,Star Quest".to_string();
+    let (quest1, quest2, quest3) = (quest1.to_gd(), quest2.to_gd(), quest3.to_gd());
 
     let list =
         format!("quest:{user_id}:{chk}:{udid}:{user_id}:{time_left}:{quest1}:{quest2}:{quest3}");
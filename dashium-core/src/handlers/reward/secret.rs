@@ -0,0 +1,72 @@
+use axum::extract::State;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+use crate::{
+    AppError,
+    auth::AuthForm,
+    models::SecretReward,
+    util::{base64_encode, cyclic_xor, decode_chk, salt_and_sha1},
+};
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct getGJSecretReward {
+    #[serde(default)]
+    accountID: i32,
+    chk: String,
+    /// The code entered in the vault.
+    rewardKey: String,
+    secret: String,
+    udid: String,
+}
+
+pub async fn getGJSecretReward(
+    State(pool): State<PgPool>,
+    AuthForm(auth, form): AuthForm<getGJSecretReward>,
+) -> Result<String, AppError> {
+    let user_id = form.accountID;
+    let udid = &form.udid;
+    let code = form.rewardKey.trim();
+
+    let Some(chk) = decode_chk(&form.chk, b"59182") else {
+        return Ok("-1".to_string());
+    };
+
+    if udid.len() > 64 {
+        return Ok("-1".to_string());
+    }
+
+    let owner = if user_id != 0 {
+        if !auth.verify(&pool).await? {
+            return Ok("-1".to_string());
+        }
+
+        Some(user_id)
+    } else {
+        None
+    };
+
+    let Some(reward) = SecretReward::get_by_code(&pool, code).await? else {
+        return Ok("-1".to_string());
+    };
+
+    // Guests could redeem single use codes again by changing their UDID
+    if reward.single_use && owner.is_none() {
+        return Ok("-1".to_string());
+    }
+
+    if !reward.redeem(&pool, owner, udid).await? {
+        return Ok("-1".to_string());
+    }
+
+    let list = format!(
+        "vault:{user_id}:{chk}:{udid}:{user_id}:{}:{}",
+        reward.id, reward.rewards
+    );
+
+    let list = base64_encode(&cyclic_xor(list.as_bytes(), b"59182")?);
+    let hash = salt_and_sha1(&list, "pC26fpYaQCtg");
+
+    let response = format!("vault{}|{}", &list, &hash);
+    Ok(response)
+}
@@ -1,49 +1,54 @@
-use axum::{Form, extract::State};
+use axum::extract::State;
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
 
-use crate::{AppError, GDResponse, models::User, util::verify_gjp2};
+use crate::{AppError, GDResponse, auth::AuthForm, models::User};
 
 #[derive(Deserialize, Serialize, Debug)]
 pub struct getGJScores20 {
     accountID: i32,
-    gjp2: String,
     #[serde(rename = "type")]
     leaderboard_type: String,
 }
 
 pub async fn getGJScores20(
     State(pool): State<PgPool>,
-    Form(form): Form<getGJScores20>,
+    AuthForm(auth, form): AuthForm<getGJScores20>,
 ) -> Result<String, AppError> {
     let user_id = form.accountID;
-    let gjp2 = &form.gjp2;
 
     let users: Vec<User> = match form.leaderboard_type.as_str() {
         "top" | "relative" => {
-            sqlx::query_as("SELECT * FROM user_view ORDER BY stars DESC")
+            sqlx::query_as("SELECT * FROM user_view WHERE rank IS NOT NULL ORDER BY rank")
                 .fetch_all(&pool)
                 .await?
         }
         "creators" => {
-            sqlx::query_as("SELECT * FROM user_view ORDER BY creator_points DESC, stars DESC")
-                .fetch_all(&pool)
-                .await?
+            sqlx::query_as(
+                r#"
+                SELECT * FROM user_view
+                WHERE rank IS NOT NULL
+                ORDER BY creator_points DESC, stars DESC
+                "#,
+            )
+            .fetch_all(&pool)
+            .await?
         }
         "friends" => {
-            if !verify_gjp2(&pool, user_id, gjp2).await? {
+            if !auth.verify(&pool).await? {
                 return Ok("-1".to_string());
             }
 
             sqlx::query_as(
                 r#"
-                SELECT user_view.*
-                FROM friendships f
-                JOIN user_view u ON 
-                    (f.user1 = $1 AND u.id = f.user2) OR
-                    (f.user2 = $1 AND u.id = f.user1)
-                UNION
-                SELECT * FROM user_view WHERE id = $1
+                SELECT * FROM user_view
+                WHERE rank IS NOT NULL
+                AND (
+                    id = $1
+                    OR id IN (SELECT user2 FROM friendships WHERE user1 = $1)
+                    OR id IN (SELECT user1 FROM friendships WHERE user2 = $1)
+                )
+                ORDER BY rank
                 "#,
             )
             .bind(user_id)
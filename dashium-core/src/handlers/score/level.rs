@@ -1,13 +1,12 @@
-use axum::{Form, extract::State};
+use axum::extract::State;
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
 
-use crate::{AppError, GDResponse, models::User, util::verify_gjp2};
+use crate::{AppError, GDResponse, auth::AuthForm, models::User};
 
 #[derive(Serialize, Deserialize)]
 pub struct getGJLevelScores211 {
     accountID: i32,
-    gjp2: String,
     levelID: i32,
     percent: i16,
     s1: i32,
@@ -21,10 +20,9 @@ pub struct getGJLevelScores211 {
 
 pub async fn getGJLevelScores211(
     State(pool): State<PgPool>,
-    Form(form): Form<getGJLevelScores211>,
+    AuthForm(auth, form): AuthForm<getGJLevelScores211>,
 ) -> Result<String, AppError> {
     let user_id = form.accountID;
-    let gjp2 = &form.gjp2;
     let level_id = form.levelID;
     let attempts = form.s1 - 8354;
     let clicks = form.s2 - 3991;
@@ -34,7 +32,7 @@ pub async fn getGJLevelScores211(
     let percent = form.percent;
     let leaderboard_type = form.leaderboard_type;
 
-    if !verify_gjp2(&pool, user_id, gjp2).await? {
+    if !auth.verify(&pool).await? {
         return Ok("-1".to_string());
     }
 
@@ -107,10 +105,11 @@ pub async fn getGJLevelScores211(
         1 => {
             sqlx::query_as(
                 r#"
-                SELECT user_view.*
+                SELECT u.*
                 FROM user_view u
                 JOIN level_scores l ON u.id = l.user_id
                 WHERE l.level_id = $1
+                AND u.rank IS NOT NULL
                 ORDER BY l.percent DESC
                 "#,
             )
@@ -122,10 +121,11 @@ pub async fn getGJLevelScores211(
         2 => {
             sqlx::query_as(
                 r#"
-                SELECT user_view.*
+                SELECT u.*
                 FROM user_view u
                 JOIN level_scores l ON u.id = l.user_id
                 WHERE l.level_id = $1
+                AND u.rank IS NOT NULL
                 AND l.created_at >= NOW() - INTERVAL '14 days'
                 ORDER BY l.percent DESC
                 "#,
@@ -144,10 +144,11 @@ pub async fn getGJLevelScores211(
 
             sqlx::query_as(
                 r#"
-                SELECT user_view.*
+                SELECT u.*
                 FROM user_view u
                 JOIN level_scores l ON u.id = l.user_id
                 WHERE l.level_id = $1
+                AND u.rank IS NOT NULL
                 AND l.user_id = ANY($2)
                 ORDER BY l.percent DESC
                 "#,
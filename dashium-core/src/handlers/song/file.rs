@@ -0,0 +1,35 @@
+use axum::{
+    extract::{Path, State},
+    http::{StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use sqlx::PgPool;
+
+use crate::{AppError, config::config, models::Song};
+
+/// Serves a custom song file from the songs directory, e.g. `/songs/12.mp3`.
+pub async fn getSongFile(
+    State(pool): State<PgPool>,
+    Path(file): Path<String>,
+) -> Result<Response, AppError> {
+    let Some(song_id) = file
+        .strip_suffix(".mp3")
+        .and_then(|id| id.parse::<i32>().ok())
+    else {
+        return Ok(StatusCode::NOT_FOUND.into_response());
+    };
+
+    match Song::get(&pool, song_id).await? {
+        Some(song) if !song.is_disabled => (),
+        _ => return Ok(StatusCode::NOT_FOUND.into_response()),
+    }
+
+    let path = config().songs_dir.join(format!("{song_id}.mp3"));
+
+    let data = match tokio::fs::read(path).await {
+        Ok(data) => data,
+        Err(_) => return Ok(StatusCode::NOT_FOUND.into_response()),
+    };
+
+    Ok(([(header::CONTENT_TYPE, "audio/mpeg")], data).into_response())
+}
@@ -0,0 +1,28 @@
+use axum::{Form, extract::State};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+use crate::{AppError, GDResponse, models::Song};
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct getGJSongInfo {
+    songID: i32,
+    secret: String,
+}
+
+pub async fn getGJSongInfo(
+    State(pool): State<PgPool>,
+    Form(form): Form<getGJSongInfo>,
+) -> Result<String, AppError> {
+    let song_id = form.songID;
+
+    let Some(song) = Song::get(&pool, song_id).await? else {
+        return Ok("-1".to_string());
+    };
+
+    if song.is_disabled {
+        return Ok("-2".to_string());
+    }
+
+    Ok(song.to_gd())
+}
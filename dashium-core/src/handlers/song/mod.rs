@@ -0,0 +1,5 @@
+mod info;
+pub use crate::song::info::getGJSongInfo;
+
+mod file;
+pub use crate::song::file::getSongFile;
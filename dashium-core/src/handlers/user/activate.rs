@@ -0,0 +1,40 @@
+use axum::{
+    extract::{Query, State},
+    response::Html,
+};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+use crate::AppError;
+use crate::auth::verify_activation_signature;
+use crate::models::User;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct activateAccount {
+    id: i32,
+    /// Unix timestamp the link expires at.
+    expires: i64,
+    sig: String,
+}
+
+pub async fn activateAccount(
+    State(pool): State<PgPool>,
+    Query(query): Query<activateAccount>,
+) -> Result<Html<&'static str>, AppError> {
+    if !verify_activation_signature(query.id, query.expires, &query.sig) {
+        return Ok(Html("Invalid activation link."));
+    }
+
+    if query.expires <= Utc::now().timestamp() {
+        return Ok(Html(
+            "This activation link has expired, register again to get a new one.",
+        ));
+    }
+
+    if !User::activate(&pool, query.id).await? {
+        return Ok(Html("Your account is already activated."));
+    }
+
+    Ok(Html("Your account has been activated, you can now log in."))
+}
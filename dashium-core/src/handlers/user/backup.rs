@@ -1,13 +1,12 @@
-use axum::{Form, extract::State};
+use axum::extract::State;
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
 
-use crate::{AppError, models::User, util::verify_gjp2};
+use crate::{AppError, auth::AuthForm, config::config, models::SaveBackup};
 
 #[derive(Deserialize, Serialize, Debug)]
 pub struct backupGJAccountNew {
     accountID: i32,
-    gjp2: String,
     gameVersion: i16,
     binaryVersion: i16,
     saveData: String,
@@ -18,16 +17,19 @@ pub struct backupGJAccountNew {
 
 pub async fn backupGJAccountNew(
     State(pool): State<PgPool>,
-    Form(form): Form<backupGJAccountNew>,
+    AuthForm(auth, form): AuthForm<backupGJAccountNew>,
 ) -> Result<String, AppError> {
     let user_id = form.accountID;
-    let gjp2 = &form.gjp2;
     let data = &form.saveData;
 
-    if !verify_gjp2(&pool, user_id, gjp2).await? {
+    if !auth.verify(&pool).await? {
         return Ok("-1".to_string());
     }
 
-    User::save_data(&pool, user_id, data).await?;
+    if data.len() > config().max_save_size {
+        return Ok("-1".to_string());
+    }
+
+    SaveBackup::create(&pool, user_id, data).await?;
     Ok("1".to_string())
 }
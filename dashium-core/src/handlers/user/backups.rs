@@ -0,0 +1,39 @@
+use axum::extract::State;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+use crate::{AppError, GDResponse, auth::AuthForm, models::SaveBackup};
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct getGJAccountBackups {
+    accountID: i32,
+    secret: String,
+}
+
+pub async fn getGJAccountBackups(
+    State(pool): State<PgPool>,
+    AuthForm(auth, form): AuthForm<getGJAccountBackups>,
+) -> Result<String, AppError> {
+    let user_id = form.accountID;
+
+    if !auth.verify(&pool).await? {
+        return Ok("-1".to_string());
+    }
+
+    let backups = SaveBackup::get_all(&pool, user_id).await?;
+
+    if backups.is_empty() {
+        return Ok("-2".to_string());
+    }
+
+    let mut response = String::new();
+
+    for backup in &backups {
+        response.push_str(&backup.to_gd());
+        response.push('|');
+    }
+
+    response.pop();
+
+    Ok(response)
+}
@@ -1,17 +1,16 @@
-use axum::{Form, extract::State};
+use axum::extract::State;
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
 
 use crate::AppError;
 use crate::GDResponse;
+use crate::auth::AuthForm;
 use crate::models::{Block, User};
-use crate::util::verify_gjp2;
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct getGJUserInfo20 {
     accountID: i32,
     targetAccountID: i32,
-    gjp2: String,
     gameVersion: i16,
     binaryVersion: i16,
     secret: String,
@@ -19,15 +18,12 @@ pub struct getGJUserInfo20 {
 
 pub async fn getGJUserInfo20(
     State(pool): State<PgPool>,
-    Form(form): Form<getGJUserInfo20>,
+    AuthForm(auth, form): AuthForm<getGJUserInfo20>,
 ) -> Result<String, AppError> {
     let user_id = form.accountID;
     let target_id = form.targetAccountID;
-    let gjp2 = &form.gjp2;
 
-    if Block::is_blocked(&pool, target_id, user_id).await?
-        && verify_gjp2(&pool, user_id, gjp2).await?
-    {
+    if Block::is_blocked(&pool, target_id, user_id).await? && auth.verify(&pool).await? {
         return Ok("-1".to_string());
     }
 
@@ -3,14 +3,20 @@ use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
 
 use crate::AppError;
+use crate::auth::{is_usable, password_to_gjp2, verify_gjp2};
 use crate::models::User;
-use crate::util::verify_gjp2;
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct loginGJAccount {
     userName: String,
+
+    #[serde(default)]
     gjp2: String,
 
+    // Sent in plain text by 2.1 clients instead of `gjp2`
+    #[serde(default)]
+    password: String,
+
     #[serde(default)]
     sID: String,
 
@@ -23,7 +29,11 @@ pub async fn loginGJAccount(
     Form(form): Form<loginGJAccount>,
 ) -> Result<String, AppError> {
     let username = &form.userName;
-    let gjp2 = &form.gjp2;
+    let gjp2 = if form.gjp2.is_empty() {
+        &password_to_gjp2(&form.password)
+    } else {
+        &form.gjp2
+    };
 
     let user_id = User::id_from_username(&pool, username).await?;
 
@@ -31,5 +41,9 @@ pub async fn loginGJAccount(
         return Ok(String::from("-11"));
     }
 
+    if !is_usable(&pool, user_id).await? {
+        return Ok(String::from("-12"));
+    }
+
     Ok(format!("{user_id},{user_id}"))
 }
@@ -0,0 +1,51 @@
+use anyhow::Result;
+use axum::{Form, extract::State};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use tracing::warn;
+
+use crate::AppError;
+use crate::config::config;
+use crate::mail;
+use crate::models::User;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct lostPassword {
+    email: String,
+}
+
+pub async fn lostPassword(
+    State(pool): State<PgPool>,
+    Form(form): Form<lostPassword>,
+) -> Result<String, AppError> {
+    // Always succeed without waiting for the email, so neither the response nor its timing
+    // tell whether the email is registered
+    tokio::spawn(async move {
+        if let Err(e) = send_reset(&pool, &form.email).await {
+            warn!("Failed to send a password reset email: {e:#}");
+        }
+    });
+
+    Ok("1".to_string())
+}
+
+async fn send_reset(pool: &PgPool, email: &str) -> Result<()> {
+    let Some((user_id, email)) = User::find_by_email(pool, email).await? else {
+        return Ok(());
+    };
+
+    let username = User::username_from_id(pool, user_id).await?;
+    let token = User::create_password_reset(pool, user_id).await?;
+
+    let link = format!(
+        "{}/accounts/resetPassword.php?token={token}",
+        config().server_url
+    );
+    let body = format!(
+        "Hi {username},\n\nSomeone asked to reset the password of your account. \
+        Open the link below to choose a new one:\n\n{link}\n\n\
+        If this wasn't you, you can ignore this email."
+    );
+
+    mail::send(&email, "Reset your password", &body).await
+}
@@ -21,3 +21,24 @@ pub use crate::user::search::getGJUsers20;
 
 mod settings;
 pub use crate::user::settings::updateGJAccSettings20;
+
+mod password;
+pub use crate::user::password::changePassword;
+
+mod username;
+pub use crate::user::username::changeUsername;
+
+mod lost;
+pub use crate::user::lost::lostPassword;
+
+mod reset;
+pub use crate::user::reset::{resetPassword, resetPasswordPage};
+
+mod activate;
+pub use crate::user::activate::activateAccount;
+
+mod backups;
+pub use crate::user::backups::getGJAccountBackups;
+
+mod restore;
+pub use crate::user::restore::restoreGJAccountBackup;
@@ -0,0 +1,43 @@
+use axum::{Form, extract::State};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+use crate::AppError;
+use crate::auth::{is_usable, password_to_gjp2, verify_gjp2};
+use crate::models::User;
+use crate::util::is_ascii_alphanumeric;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct changePassword {
+    userName: String,
+    password: String,
+    newPassword: String,
+}
+
+pub async fn changePassword(
+    State(pool): State<PgPool>,
+    Form(form): Form<changePassword>,
+) -> Result<String, AppError> {
+    let new_password = &form.newPassword;
+
+    let user_id = User::id_from_username(&pool, &form.userName).await?;
+
+    if !verify_gjp2(&pool, user_id, &password_to_gjp2(&form.password)).await? {
+        return Ok(String::from("-11"));
+    }
+
+    if !is_usable(&pool, user_id).await? {
+        return Ok(String::from("-12"));
+    }
+
+    if new_password.len() < 8 {
+        return Ok(String::from("-8"));
+    }
+
+    if !is_ascii_alphanumeric(new_password) {
+        return Ok(String::from("-5"));
+    }
+
+    User::update_password(&pool, user_id, new_password).await?;
+    Ok("1".to_string())
+}
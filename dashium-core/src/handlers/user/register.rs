@@ -1,9 +1,14 @@
 use axum::Form;
 use axum::extract::State;
+use chrono::Utc;
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
+use tracing::warn;
 
 use crate::AppError;
+use crate::auth::activation_signature;
+use crate::config::config;
+use crate::mail;
 use crate::models::User;
 use crate::util::is_ascii_alphanumeric;
 
@@ -43,6 +48,10 @@ pub async fn registerGJAccount(
         return Ok(String::from("-5"));
     }
 
+    if config().account_activation {
+        User::delete_expired_unactivated(&pool, username, email).await?;
+    }
+
     if User::is_username_taken(&pool, username).await? {
         return Ok(String::from("-2"));
     }
@@ -51,6 +60,34 @@ pub async fn registerGJAccount(
         return Ok(String::from("-3"));
     }
 
-    User::create(&pool, username, password, email).await?;
+    let mut tx = pool.begin().await?;
+
+    if !config().account_activation {
+        User::create(&mut tx, username, password, email, true).await?;
+        tx.commit().await?;
+        return Ok("1".to_string());
+    }
+
+    let user_id = User::create(&mut tx, username, password, email, false).await?;
+    let expires_at = Utc::now().timestamp() + config().activation_link_ttl;
+
+    let link = format!(
+        "{}/accounts/activateAccount.php?id={user_id}&expires={expires_at}&sig={}",
+        config().server_url,
+        activation_signature(user_id, expires_at)
+    );
+    let body = format!(
+        "Hi {username},\n\nThanks for registering! Open the link below to activate your \
+        account:\n\n{link}"
+    );
+
+    // The account could never be activated without the email, so it isn't kept
+    if let Err(e) = mail::send(email, "Activate your account", &body).await {
+        warn!("Failed to send activation email to user {user_id}: {e}");
+        return Ok("-1".to_string());
+    }
+
+    tx.commit().await?;
+
     Ok("1".to_string())
 }
@@ -0,0 +1,67 @@
+use axum::{
+    Form,
+    extract::{Query, State},
+    response::Html,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+use crate::AppError;
+use crate::models::User;
+use crate::util::is_ascii_alphanumeric;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct resetPasswordPage {
+    token: String,
+}
+
+pub async fn resetPasswordPage(Query(query): Query<resetPasswordPage>) -> Html<String> {
+    // The token is hex, anything else would just fail the lookup
+    let token: String = query
+        .token
+        .chars()
+        .filter(|c| c.is_ascii_hexdigit())
+        .collect();
+
+    Html(format!(
+        r#"<!DOCTYPE html>
+<html>
+<head><title>Reset password</title></head>
+<body>
+<form method="post">
+<input type="hidden" name="token" value="{token}">
+<label>New password <input type="password" name="newPassword" minlength="8"></label>
+<button type="submit">Reset</button>
+</form>
+</body>
+</html>"#
+    ))
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct resetPassword {
+    token: String,
+    newPassword: String,
+}
+
+pub async fn resetPassword(
+    State(pool): State<PgPool>,
+    Form(form): Form<resetPassword>,
+) -> Result<String, AppError> {
+    let new_password = &form.newPassword;
+
+    if new_password.len() < 8 {
+        return Ok(String::from("-8"));
+    }
+
+    if !is_ascii_alphanumeric(new_password) {
+        return Ok(String::from("-5"));
+    }
+
+    let Some(user_id) = User::redeem_password_reset(&pool, &form.token).await? else {
+        return Ok(String::from("-1"));
+    };
+
+    User::update_password(&pool, user_id, new_password).await?;
+    Ok("1".to_string())
+}
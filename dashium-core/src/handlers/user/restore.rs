@@ -0,0 +1,30 @@
+use axum::extract::State;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+use crate::{AppError, auth::AuthForm, models::SaveBackup};
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct restoreGJAccountBackup {
+    accountID: i32,
+    backupID: i32,
+    secret: String,
+}
+
+pub async fn restoreGJAccountBackup(
+    State(pool): State<PgPool>,
+    AuthForm(auth, form): AuthForm<restoreGJAccountBackup>,
+) -> Result<String, AppError> {
+    let user_id = form.accountID;
+    let backup_id = form.backupID;
+
+    if !auth.verify(&pool).await? {
+        return Ok("-1".to_string());
+    }
+
+    if !SaveBackup::restore(&pool, user_id, backup_id).await? {
+        return Ok("-1".to_string());
+    }
+
+    Ok("1".to_string())
+}
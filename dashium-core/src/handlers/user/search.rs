@@ -6,7 +6,6 @@ use sqlx::PgPool;
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct getGJUsers20 {
-    gjp2: String,
     str: String,
     page: i32,
     total: i32,
@@ -1,13 +1,12 @@
 use crate::AppError;
-use crate::util::verify_gjp2;
-use axum::{Form, extract::State};
+use crate::auth::AuthForm;
+use axum::extract::State;
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct updateGJAccSettings20 {
     accountID: i32,
-    gjp2: String,
     mS: i16,
     frS: i16,
     cS: i16,
@@ -19,10 +18,9 @@ pub struct updateGJAccSettings20 {
 
 pub async fn updateGJAccSettings20(
     State(pool): State<PgPool>,
-    Form(form): Form<updateGJAccSettings20>,
+    AuthForm(auth, form): AuthForm<updateGJAccSettings20>,
 ) -> Result<String, AppError> {
     let user_id = form.accountID;
-    let gjp2 = &form.gjp2;
     let message_setting = form.mS;
     let friend_setting = form.frS;
     let comment_setting = form.cS;
@@ -30,7 +28,7 @@ pub async fn updateGJAccSettings20(
     let twitter = &form.twitter;
     let twitch = &form.twitch;
 
-    if !verify_gjp2(&pool, user_id, gjp2).await? {
+    if !auth.verify(&pool).await? {
         return Ok("-1".to_string());
     }
 
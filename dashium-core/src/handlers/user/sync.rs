@@ -1,13 +1,12 @@
-use axum::{Form, extract::State};
+use axum::extract::State;
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
 
-use crate::{AppError, models::User, util::verify_gjp2};
+use crate::{AppError, auth::AuthForm, models::SaveBackup};
 
 #[derive(Deserialize, Serialize, Debug)]
 pub struct syncGJAccountNew {
     accountID: i32,
-    gjp2: String,
     gameVersion: i16,
     binaryVersion: i16,
     secret: String,
@@ -17,15 +16,17 @@ pub struct syncGJAccountNew {
 
 pub async fn syncGJAccountNew(
     State(pool): State<PgPool>,
-    Form(form): Form<syncGJAccountNew>,
+    AuthForm(auth, form): AuthForm<syncGJAccountNew>,
 ) -> Result<String, AppError> {
     let user_id = form.accountID;
-    let gjp2 = &form.gjp2;
 
-    if !verify_gjp2(&pool, user_id, gjp2).await? {
+    if !auth.verify(&pool).await? {
         return Ok("-1".to_string());
     }
 
-    let save_data = User::load_data(&pool, user_id).await?;
+    let Some(save_data) = SaveBackup::load(&pool, user_id, None).await? else {
+        return Ok("-1".to_string());
+    };
+
     Ok(format!("{save_data};21;30;a;a"))
 }
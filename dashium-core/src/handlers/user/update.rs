@@ -1,15 +1,17 @@
-use axum::{Form, extract::State};
+use axum::extract::State;
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
 
 use crate::AppError;
-use crate::util::verify_gjp2;
+use crate::auth::AuthForm;
+use crate::models::Level;
+use crate::services::anticheat::{self, Stats, Verdict};
+use crate::util::verify_chk;
 
 #[derive(Serialize, Debug, Deserialize)]
 pub struct updateGJUserScore22 {
     accountID: i32,
     userName: String,
-    gjp2: String,
     seed: String,
     seed2: String,
     stars: i32,
@@ -52,10 +54,9 @@ pub struct updateGJUserScore22 {
 
 pub async fn updateGJUserScore22(
     State(pool): State<PgPool>,
-    Form(form): Form<updateGJUserScore22>,
+    AuthForm(auth, form): AuthForm<updateGJUserScore22>,
 ) -> Result<String, AppError> {
     let user_id = form.accountID;
-    let gjp2 = &form.gjp2;
 
     let stars = form.stars;
     let moons = form.moons;
@@ -82,13 +83,47 @@ pub async fn updateGJUserScore22(
     let jetpack = form.accJetpack;
     let glow = form.accGlow;
 
-    let level_info = &form.sinfo;
+    if !auth.verify(&pool).await? {
+        return Ok("-1".to_string());
+    }
+
+    // `sinfo` holds the completed non-demon levels by difficulty (auto to insane),
+    // the six classic counts first and then the six platformer ones
+    let completed = parse_counts(&form.sinfo, 12);
+    let level_info = &join_counts(
+        completed[..6]
+            .iter()
+            .chain(&[form.sinfod, form.sinfog])
+            .copied(),
+    );
+    let platformer_info = &join_counts(completed[6..].iter().copied());
+
+    // `dinfo` lists the IDs of the completed online demons
+    let demon_ids: Vec<i32> = form
+        .dinfo
+        .split(',')
+        .filter_map(|id| id.parse().ok())
+        .collect();
+    let demons_completed = Level::count_demons(&pool, &demon_ids).await?;
+    let demon_info = &join_counts(
+        demons_completed
+            .iter()
+            .chain(&[form.dinfow, form.dinfog])
+            .copied(),
+    );
+
+    let stats = Stats {
+        stars,
+        moons,
+        demons,
+        diamonds,
+        coins,
+        user_coins,
+    };
 
-    // FIXME: Construct these properly later
-    let demon_info = "0,0,0,0,0,0,0,0,0,0,0,0";
-    let platformer_info = "0,0,0,0,0,0";
+    let verdict = anticheat::review(&pool, user_id, &stats, verify_seed2(&form)).await?;
 
-    if !verify_gjp2(&pool, user_id, gjp2).await? {
+    if let Verdict::Rejected(_) = verdict {
         return Ok("-1".to_string());
     }
 
@@ -159,3 +194,50 @@ pub async fn updateGJUserScore22(
 
     Ok(user_id.to_string())
 }
+
+/// Parses a list of comma-separated counts, padded or truncated to `len` entries.
+fn parse_counts(list: &str, len: usize) -> Vec<i32> {
+    let mut counts: Vec<i32> = list
+        .split(',')
+        .map(|count| count.parse().unwrap_or_default())
+        .collect();
+
+    counts.resize(len, 0);
+    counts
+}
+
+fn join_counts(counts: impl Iterator<Item = i32>) -> String {
+    counts.map(|c| c.to_string()).collect::<Vec<_>>().join(",")
+}
+
+fn verify_seed2(form: &updateGJUserScore22) -> bool {
+    let demon_count = form.dinfo.split(',').filter(|id| !id.is_empty()).count();
+
+    let values = [
+        form.accountID.to_string(),
+        form.userCoins.to_string(),
+        form.demons.to_string(),
+        form.stars.to_string(),
+        form.coins.to_string(),
+        form.iconType.to_string(),
+        form.icon.to_string(),
+        form.diamonds.to_string(),
+        form.accIcon.to_string(),
+        form.accShip.to_string(),
+        form.accBall.to_string(),
+        form.accBird.to_string(),
+        form.accDart.to_string(),
+        form.accRobot.to_string(),
+        form.accGlow.to_string(),
+        form.accSpider.to_string(),
+        form.accExplosion.to_string(),
+        demon_count.to_string(),
+        form.dinfow.to_string(),
+        form.dinfog.to_string(),
+        form.sinfo.clone(),
+        form.sinfod.to_string(),
+        form.sinfog.to_string(),
+    ];
+
+    verify_chk(&form.seed2, &values, "xI35fsAapCRg", b"85271")
+}
@@ -0,0 +1,55 @@
+use axum::{Form, extract::State};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+use crate::AppError;
+use crate::auth::{is_usable, password_to_gjp2, verify_gjp2};
+use crate::models::User;
+use crate::util::is_ascii_alphanumeric;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct changeUsername {
+    userName: String,
+    password: String,
+    newUserName: String,
+}
+
+pub async fn changeUsername(
+    State(pool): State<PgPool>,
+    Form(form): Form<changeUsername>,
+) -> Result<String, AppError> {
+    let username = &form.userName;
+    let new_username = &form.newUserName;
+
+    let user_id = User::id_from_username(&pool, username).await?;
+
+    if !verify_gjp2(&pool, user_id, &password_to_gjp2(&form.password)).await? {
+        return Ok(String::from("-11"));
+    }
+
+    if !is_usable(&pool, user_id).await? {
+        return Ok(String::from("-12"));
+    }
+
+    if new_username.len() > 20 {
+        return Ok(String::from("-4"));
+    }
+
+    if !is_ascii_alphanumeric(new_username) {
+        return Ok(String::from("-4"));
+    }
+
+    if new_username.len() < 3 {
+        return Ok(String::from("-9"));
+    }
+
+    // Changing only the capitalization of your own name is allowed
+    if !new_username.eq_ignore_ascii_case(username)
+        && User::is_username_taken(&pool, new_username).await?
+    {
+        return Ok(String::from("-2"));
+    }
+
+    User::update_username(&pool, user_id, new_username).await?;
+    Ok("1".to_string())
+}
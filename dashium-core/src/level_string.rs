@@ -0,0 +1,320 @@
+//! Decoder for GD level strings.
+//!
+//! A level string is a URL-safe Base64 encoded gzip stream, or a zlib one for levels saved by
+//! older versions of the game. Once decompressed it is a list of
+//! `;`-separated objects, each being a `,`-separated list of key-value pairs.
+//! The first object holds the start settings of the level (`kS38,...,kA2,0,...`),
+//! every following one is a placed object (`1,1,2,15,3,15,...`).
+
+use std::{collections::HashMap, io::Read};
+
+use anyhow::{Result, bail};
+use base64::{
+    Engine as _,
+    engine::{DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig},
+};
+use flate2::read::{GzDecoder, ZlibDecoder};
+
+use crate::config::config;
+
+const BASE64: GeneralPurpose = GeneralPurpose::new(
+    &base64::alphabet::URL_SAFE,
+    GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
+
+const USER_COIN_ID: u32 = 1329;
+
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// The client stops counting user coins past this many.
+const MAX_USER_COINS: usize = 3;
+
+/// The object count sent by the client isn't always exact, so it may be off by this many
+/// objects, or by one percent on bigger levels.
+const OBJECT_COUNT_TOLERANCE: usize = 10;
+
+/// A decoded level string. Placed objects are only counted, since a level can have hundreds of
+/// thousands of them.
+#[derive(Debug)]
+pub struct LevelString {
+    pub settings: StartSettings,
+    objects: usize,
+    user_coins: usize,
+    high_detail: bool,
+}
+
+/// Settings from the level's start object.
+#[derive(Debug, Default)]
+pub struct StartSettings {
+    pub game_mode: u8,
+    pub speed: u8,
+    pub mini: bool,
+    pub dual: bool,
+    pub two_player: bool,
+    pub flip_gravity: bool,
+    pub platformer: bool,
+    pub song_offset: f32,
+    /// Every key-value pair of the start object, including the ones above.
+    pub properties: HashMap<String, String>,
+}
+
+impl LevelString {
+    /// Decodes a Base64 + gzip (or zlib) level string as uploaded by the client.
+    ///
+    /// Fails if the data is corrupt, bigger than the `MAX_LEVEL_SIZE` setting once decompressed
+    /// or has more objects than the `MAX_LEVEL_OBJECTS` setting.
+    pub fn decode(level_string: &str) -> Result<Self> {
+        let compressed = BASE64.decode(level_string.trim())?;
+        let max_size = config().max_level_size;
+
+        let decoder: Box<dyn Read> = if compressed.starts_with(&GZIP_MAGIC) {
+            Box::new(GzDecoder::new(compressed.as_slice()))
+        } else {
+            Box::new(ZlibDecoder::new(compressed.as_slice()))
+        };
+
+        let mut data = Vec::new();
+        decoder.take(max_size as u64 + 1).read_to_end(&mut data)?;
+
+        if data.len() > max_size {
+            bail!("level string exceeds {max_size} bytes");
+        }
+
+        Self::parse(&String::from_utf8(data)?)
+    }
+
+    /// Parses an already decompressed level string.
+    pub fn parse(data: &str) -> Result<Self> {
+        let max_objects = config().max_level_objects;
+        let mut records = data.split(';');
+
+        let settings = match records.next() {
+            Some(record) if !record.is_empty() => StartSettings::parse(record)?,
+            _ => bail!("level string has no start object"),
+        };
+
+        let mut level = Self {
+            settings,
+            objects: 0,
+            user_coins: 0,
+            high_detail: false,
+        };
+
+        for record in records.filter(|record| !record.is_empty()) {
+            level.objects += 1;
+
+            if level.objects > max_objects {
+                bail!("level string has more than {max_objects} objects");
+            }
+
+            let (id, high_detail) = scan_object(record)?;
+
+            if id == USER_COIN_ID {
+                level.user_coins += 1;
+            }
+
+            level.high_detail |= high_detail;
+        }
+
+        Ok(level)
+    }
+
+    pub fn object_count(&self) -> usize {
+        self.objects
+    }
+
+    pub fn user_coins(&self) -> usize {
+        self.user_coins
+    }
+
+    /// Returns `true` if the object and user coin counts sent by the client agree with the level.
+    pub fn matches_counts(&self, objects: usize, coins: usize) -> bool {
+        let tolerance = (objects / 100).max(OBJECT_COUNT_TOLERANCE);
+
+        self.object_count().abs_diff(objects) <= tolerance
+            && self.user_coins().min(MAX_USER_COINS) == coins
+    }
+
+    /// Returns `true` if the level has objects that Low Detail Mode can hide.
+    pub fn has_ldm(&self) -> bool {
+        self.high_detail
+    }
+}
+
+impl StartSettings {
+    fn parse(record: &str) -> Result<Self> {
+        let properties: HashMap<String, String> = pairs(record)?
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+
+        let number = |key: &str| {
+            properties
+                .get(key)
+                .and_then(|v| v.parse().ok())
+                .unwrap_or_default()
+        };
+        let flag = |key: &str| properties.get(key).is_some_and(|v| v == "1");
+
+        Ok(Self {
+            game_mode: number("kA2"),
+            speed: number("kA4"),
+            mini: flag("kA3"),
+            dual: flag("kA8"),
+            two_player: flag("kA10"),
+            flip_gravity: flag("kA11"),
+            platformer: flag("kA22"),
+            song_offset: properties
+                .get("kA13")
+                .and_then(|v| v.parse().ok())
+                .unwrap_or_default(),
+            properties,
+        })
+    }
+}
+
+/// Checks a placed object and returns its ID and whether it's hidden when Low Detail Mode is
+/// enabled, without keeping the rest of its properties.
+fn scan_object(record: &str) -> Result<(u32, bool)> {
+    let mut fields = record.split(',');
+    let mut id = None;
+    let mut high_detail = false;
+
+    while let Some(key) = fields.next() {
+        let Some(value) = fields.next() else {
+            bail!("odd number of fields in record: {record}");
+        };
+
+        match key.parse::<u32>()? {
+            1 => id = value.parse().ok(),
+            103 => high_detail = value == "1",
+            _ => (),
+        }
+    }
+
+    let Some(id) = id else {
+        bail!("object without a valid ID: {record}");
+    };
+
+    Ok((id, high_detail))
+}
+
+fn pairs(record: &str) -> Result<Vec<(&str, &str)>> {
+    let parts: Vec<&str> = record.split(',').collect();
+
+    if !parts.len().is_multiple_of(2) {
+        bail!("odd number of fields in record: {record}");
+    }
+
+    Ok(parts.chunks(2).map(|pair| (pair[0], pair[1])).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use flate2::{
+        Compression,
+        write::{GzEncoder, ZlibEncoder},
+    };
+
+    use super::*;
+
+    /// A short platformer level as saved by 2.2: a start object with the color channels and
+    /// settings, a block, a spike, two user coins and a detail object hidden in LDM.
+    const LEVEL: &str = "kS38,1_40_2_125_3_255_11_255_12_255_13_255_4_-1_6_1000_7_1_15_1_18_0_8_1|\
+        1_0_2_102_3_255_11_255_12_255_13_255_4_-1_6_1001_7_1_15_1_18_0_8_1|,\
+        kA13,0,kA15,0,kA16,0,kA14,,kA6,0,kA7,0,kA25,0,kA17,0,kA18,0,kS39,0,kA2,0,kA3,0,kA8,0,\
+        kA4,1,kA9,0,kA10,0,kA22,1,kA23,0,kA24,0,kA27,1,kA40,1,kA41,1,kA42,1,kA28,0,kA29,0,\
+        kA31,1,kA32,1,kA36,0,kA43,0,kA44,0,kA45,1,kA33,1,kA34,1,kA35,0,kA37,1,kA38,1,kA39,1,\
+        kA19,0,kA26,0,kA20,0,kA21,0,kA11,0;\
+        1,1,2,15,3,15;1,8,2,285,3,15;1,1329,2,345,3,75;1,1329,2,405,3,75;\
+        1,503,2,165,3,45,103,1;";
+
+    fn gzip(data: &str) -> String {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(data.as_bytes()).unwrap();
+        BASE64.encode(encoder.finish().unwrap())
+    }
+
+    fn zlib(data: &str) -> String {
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(data.as_bytes()).unwrap();
+        BASE64.encode(encoder.finish().unwrap())
+    }
+
+    #[test]
+    fn decodes_gzip_and_zlib() {
+        let gzip = gzip(LEVEL);
+        let zlib = zlib(LEVEL);
+
+        assert!(gzip.starts_with("H4sI"));
+        assert!(zlib.starts_with("eJ"));
+
+        for encoded in [gzip, zlib] {
+            let level = LevelString::decode(&encoded).unwrap();
+
+            assert_eq!(level.object_count(), 5);
+            assert_eq!(level.user_coins(), 2);
+            assert!(level.has_ldm());
+            assert!(level.settings.platformer);
+            assert_eq!(level.settings.speed, 1);
+        }
+    }
+
+    #[test]
+    fn rejects_corrupt_level_strings() {
+        assert!(LevelString::decode("").is_err());
+        assert!(LevelString::decode("not base64!").is_err());
+        assert!(LevelString::decode(&BASE64.encode(LEVEL)).is_err());
+        assert!(LevelString::decode(&gzip("")).is_err());
+        assert!(LevelString::decode(&gzip("kS38,1;1,abc,2,15")).is_err());
+        assert!(LevelString::decode(&gzip("kS38,1;1,1,2")).is_err());
+        assert!(LevelString::decode(&gzip("kS38,1;a,1,2,15")).is_err());
+        assert!(LevelString::decode(&gzip("kS38,1;2,15,3,15")).is_err());
+
+        let gzip = gzip(LEVEL);
+        assert!(LevelString::decode(&gzip[..gzip.len() / 2]).is_err());
+    }
+
+    #[test]
+    fn allows_small_object_count_differences() {
+        let level = LevelString::parse(LEVEL).unwrap();
+
+        assert!(level.matches_counts(5, 2));
+        assert!(level.matches_counts(0, 2));
+        assert!(level.matches_counts(15, 2));
+        assert!(!level.matches_counts(16, 2));
+        assert!(!level.matches_counts(5, 1));
+        assert!(!level.matches_counts(5, 3));
+    }
+
+    #[test]
+    fn scales_the_tolerance_with_the_level_size() {
+        let objects = "1,1,2,15,3,15;".repeat(5000);
+        let level = LevelString::parse(&format!("kS38,1;{objects}")).unwrap();
+
+        assert!(level.matches_counts(5050, 0));
+        assert!(!level.matches_counts(5051, 0));
+    }
+
+    #[test]
+    fn rejects_too_many_objects() {
+        let max_objects = config().max_level_objects;
+
+        let objects = "1,1;".repeat(max_objects);
+        assert!(LevelString::parse(&format!("kS38,1;{objects}")).is_ok());
+
+        let objects = "1,1;".repeat(max_objects + 1);
+        assert!(LevelString::parse(&format!("kS38,1;{objects}")).is_err());
+    }
+
+    #[test]
+    fn caps_user_coins() {
+        let coins = "1,1329,2,15,3,15;".repeat(5);
+        let level = LevelString::parse(&format!("kS38,1;{coins}")).unwrap();
+
+        assert!(level.matches_counts(5, 3));
+        assert!(!level.matches_counts(5, 5));
+    }
+}
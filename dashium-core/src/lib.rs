@@ -1,16 +1,29 @@
 #![allow(non_snake_case)]
 #![allow(non_camel_case_types)]
 
-use axum::{Router, routing::post};
+use axum::{
+    Router,
+    extract::DefaultBodyLimit,
+    routing::{get, post},
+};
 use sqlx::PgPool;
 
+pub mod auth;
+pub mod client_ip;
+pub mod config;
+
 mod error;
 pub use crate::error::AppError;
 
 pub mod handlers;
 use handlers::*;
 
+pub mod level_string;
+pub mod mail;
 pub mod models;
+pub mod save_data;
+pub mod services;
+pub mod storage;
 pub mod util;
 
 pub trait GDResponse {
@@ -24,14 +37,35 @@ pub fn routes() -> Router<PgPool> {
             post(user::registerGJAccount),
         )
         .route("/accounts/loginGJAccount.php", post(user::loginGJAccount))
+        .route("/accounts/activateAccount.php", get(user::activateAccount))
+        .route("/accounts/changePassword.php", post(user::changePassword))
+        .route("/accounts/changeUsername.php", post(user::changeUsername))
+        .route("/accounts/lostPassword.php", post(user::lostPassword))
+        .route(
+            "/accounts/resetPassword.php",
+            get(user::resetPasswordPage).post(user::resetPassword),
+        )
         .route(
             "/database/accounts/backupGJAccountNew.php",
-            post(user::backupGJAccountNew),
+            post(user::backupGJAccountNew)
+                // The save data is percent-encoded, which can triple its size,
+                // plus some room for the other form fields
+                .layer(DefaultBodyLimit::max(
+                    config::config().max_save_size * 3 + 4096,
+                )),
         )
         .route(
             "/database/accounts/syncGJAccountNew.php",
             post(user::syncGJAccountNew),
         )
+        .route(
+            "/database/accounts/getGJAccountBackups.php",
+            post(user::getGJAccountBackups),
+        )
+        .route(
+            "/database/accounts/restoreGJAccountBackup.php",
+            post(user::restoreGJAccountBackup),
+        )
         .route("/updateGJUserScore22.php", post(user::updateGJUserScore22))
         .route("/getGJUserInfo20.php", post(user::getGJUserInfo20))
         .route("/getGJUsers20.php", post(user::getGJUsers20))
@@ -99,9 +133,37 @@ pub fn routes() -> Router<PgPool> {
         .route("/reportGJLevel.php", post(level::reportGJLevel))
         .route("/getGJDailyLevel.php", post(level::getGJDailyLevel))
         .route("/updateGJDesc20.php", post(level::updateGJDesc20))
+        .route("/getGJLevelVersions.php", post(level::getGJLevelVersions))
+        .route("/rollbackGJLevel.php", post(level::rollbackGJLevel))
         // Moderator
         .route("/requestUserAccess.php", post(moderator::requestUserAccess))
         .route("/suggestGJStars20.php", post(moderator::suggestGJStars20))
+        .route("/rateGJStars211.php", post(moderator::rateGJStars211))
+        .route("/rateGJDemon21.php", post(moderator::rateGJDemon21))
+        .route("/getGJSaveDataInfo.php", post(moderator::getGJSaveDataInfo))
+        .route(
+            "/getGJStatSubmissions.php",
+            post(moderator::getGJStatSubmissions),
+        )
+        .route("/getGJLevelReports.php", post(moderator::getGJLevelReports))
+        .route(
+            "/dismissGJLevelReports.php",
+            post(moderator::dismissGJLevelReports),
+        )
+        .route("/actGJLevelReports.php", post(moderator::actGJLevelReports))
+        .route("/getGJRestrictions.php", post(moderator::getGJRestrictions))
+        .route("/addGJRestriction.php", post(moderator::addGJRestriction))
+        .route("/liftGJRestriction.php", post(moderator::liftGJRestriction))
+        .route(
+            "/scheduleGJDailyLevel.php",
+            post(moderator::scheduleGJDailyLevel),
+        )
+        .route("/getGJDailyQueue.php", post(moderator::getGJDailyQueue))
+        .route(
+            "/unscheduleGJDailyLevel.php",
+            post(moderator::unscheduleGJDailyLevel),
+        )
+        .route("/createGJEvent.php", post(moderator::createGJEvent))
         // Comments
         .route("/uploadGJComment21.php", post(comment::uploadGJComment21))
         .route("/getGJComments21.php", post(comment::getGJComments21))
@@ -122,4 +184,9 @@ pub fn routes() -> Router<PgPool> {
         .route("/getGJLevelScores211.php", post(score::getGJLevelScores211))
         // Rewards
         .route("/getGJChallenges.php", post(reward::getGJChallenges))
+        .route("/getGJRewards.php", post(reward::getGJRewards))
+        .route("/getGJSecretReward.php", post(reward::getGJSecretReward))
+        // Songs
+        .route("/getGJSongInfo.php", post(song::getGJSongInfo))
+        .route("/songs/{file}", get(song::getSongFile))
 }
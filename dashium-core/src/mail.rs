@@ -0,0 +1,122 @@
+//! Outgoing email.
+//!
+//! The backend is selected with `MAILER`:
+//! - `smtp` sends through the relay configured with `SMTP_HOST`, `SMTP_PORT`, `SMTP_TLS`
+//!   (`none`, `starttls` or `tls`) and optionally `SMTP_USERNAME` and `SMTP_PASSWORD`.
+//! - `file` writes every email to an `.eml` file in `MAIL_DIR`.
+//! - `log` only logs the headers of the emails, which is handy for development. Bodies are left
+//!   out since they hold password reset and activation links, use `file` to read them.
+
+use std::{path::PathBuf, sync::LazyLock};
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::Utc;
+use lettre::{
+    AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
+    transport::smtp::authentication::Credentials,
+};
+use tokio::fs;
+use tracing::info;
+
+use crate::{config::config, util::random_token};
+
+static MAILER: LazyLock<Box<dyn Mailer>> = LazyLock::new(|| {
+    let config = config();
+
+    match config.mailer.as_str() {
+        "file" => Box::new(FileMailer::new(&config.mail_dir)),
+        "log" => Box::new(LogMailer),
+        _ => Box::new(SmtpMailer),
+    }
+});
+
+/// Returns the mailer selected by the `MAILER` setting.
+pub fn mailer() -> &'static dyn Mailer {
+    MAILER.as_ref()
+}
+
+/// Sends a plain text email through the configured mailer.
+pub async fn send(to: &str, subject: &str, body: &str) -> Result<()> {
+    mailer().send(&build(to, subject, body)?).await
+}
+
+fn build(to: &str, subject: &str, body: &str) -> Result<Message> {
+    let email = Message::builder()
+        .from(config().smtp_from.parse()?)
+        .to(to.parse()?)
+        .subject(subject)
+        .body(body.to_string())?;
+
+    Ok(email)
+}
+
+/// A way of delivering emails.
+#[async_trait]
+pub trait Mailer: Send + Sync {
+    async fn send(&self, email: &Message) -> Result<()>;
+}
+
+/// Sends emails through an SMTP relay.
+pub struct SmtpMailer;
+
+#[async_trait]
+impl Mailer for SmtpMailer {
+    async fn send(&self, email: &Message) -> Result<()> {
+        let config = config();
+
+        let mut transport = match config.smtp_tls.as_str() {
+            "tls" => AsyncSmtpTransport::<Tokio1Executor>::relay(&config.smtp_host)?,
+            "starttls" => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&config.smtp_host)?,
+            _ => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&config.smtp_host),
+        }
+        .port(config.smtp_port);
+
+        if !config.smtp_username.is_empty() {
+            transport = transport.credentials(Credentials::new(
+                config.smtp_username.clone(),
+                config.smtp_password.clone(),
+            ));
+        }
+
+        transport.build().send(email.clone()).await?;
+        Ok(())
+    }
+}
+
+/// Writes every email to `{root}/{timestamp}-{random}.eml`.
+pub struct FileMailer {
+    root: PathBuf,
+}
+
+impl FileMailer {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+}
+
+#[async_trait]
+impl Mailer for FileMailer {
+    async fn send(&self, email: &Message) -> Result<()> {
+        let name = format!(
+            "{}-{}.eml",
+            Utc::now().format("%Y%m%d%H%M%S"),
+            &random_token()[..8]
+        );
+
+        fs::create_dir_all(&self.root).await?;
+        fs::write(self.root.join(name), email.formatted()).await?;
+        Ok(())
+    }
+}
+
+/// Logs the headers of every email instead of sending it.
+pub struct LogMailer;
+
+#[async_trait]
+impl Mailer for LogMailer {
+    async fn send(&self, email: &Message) -> Result<()> {
+        info!("Email:\n{}", email.headers());
+        Ok(())
+    }
+}
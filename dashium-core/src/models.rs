@@ -14,6 +14,7 @@ pub use crate::models::relationship::Friendship;
 
 mod level;
 pub use crate::models::level::Level;
+pub use crate::models::level::LevelVersion;
 
 mod comment;
 pub use crate::models::comment::Comment;
@@ -24,3 +25,41 @@ pub use crate::models::levelpack::MapPack;
 
 mod list;
 pub use crate::models::list::List;
+
+mod song;
+pub use crate::models::song::Song;
+
+mod save_backup;
+pub use crate::models::save_backup::SaveBackup;
+
+mod stat_submission;
+pub use crate::models::stat_submission::StatSubmission;
+
+mod item_like;
+pub use crate::models::item_like::ItemLike;
+pub use crate::models::item_like::Vote;
+
+mod level_report;
+pub use crate::models::level_report::LevelReport;
+
+mod restriction;
+pub use crate::models::restriction::Restriction;
+pub use crate::models::restriction::RestrictionKind;
+
+mod scheduled_level;
+pub use crate::models::scheduled_level::ScheduleKind;
+pub use crate::models::scheduled_level::ScheduledLevel;
+
+mod event;
+pub use crate::models::event::Event;
+
+mod quest;
+pub use crate::models::quest::IssuedQuest;
+pub use crate::models::quest::Quest;
+
+mod reward_chest;
+pub use crate::models::reward_chest::ChestKind;
+pub use crate::models::reward_chest::RewardChests;
+
+mod secret_reward;
+pub use crate::models::secret_reward::SecretReward;
@@ -76,8 +76,8 @@ impl Comment {
                 c.spam,
                 c.created_at,
                 c.percent,
-                c.chat_color,
-                u.mod_level,
+                COALESCE(r.chat_color, '255,255,255') AS chat_color,
+                COALESCE(r.badge, 0::SMALLINT) AS mod_level,
                 u.display_icon,
                 u.color1,
                 u.color2,
@@ -85,6 +85,7 @@ impl Comment {
                 u.glow
             FROM comments c
             JOIN users u ON u.id = c.user_id
+            LEFT JOIN roles r ON r.id = u.role_id
             WHERE c.level_id = 
             "#,
         );
@@ -113,8 +114,8 @@ impl Comment {
                 c.spam,
                 c.created_at,
                 c.percent,
-                c.chat_color,
-                u.mod_level,
+                COALESCE(r.chat_color, '255,255,255') AS chat_color,
+                COALESCE(r.badge, 0::SMALLINT) AS mod_level,
                 u.display_icon,
                 u.color1,
                 u.color2,
@@ -122,6 +123,7 @@ impl Comment {
                 u.glow
             FROM comments c
             JOIN users u ON u.id = c.user_id
+            LEFT JOIN roles r ON r.id = u.role_id
             WHERE c.user_id = 
             "#,
         );
@@ -171,4 +173,17 @@ impl Comment {
 
         Ok(())
     }
+
+    /// Deletes a comment regardless of who posted it.
+    pub async fn remove(pool: &PgPool, level_id: i32, comment_id: i32) -> Result<()> {
+        sqlx::query!(
+            "DELETE FROM comments WHERE level_id = $1 AND id = $2",
+            level_id,
+            comment_id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
 }
@@ -0,0 +1,86 @@
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use sqlx::{PgPool, prelude::FromRow};
+
+use crate::GDResponse;
+
+/// The rewards and length of an event level.
+#[derive(Debug, FromRow, Serialize)]
+pub struct Event {
+    pub id: i32,
+    /// Shown by the client, which also uses it to remember claimed rewards.
+    pub number: i32,
+    /// In seconds.
+    pub duration: i32,
+    /// Item types and amounts, as `type,amount,type,amount,...`.
+    pub rewards: String,
+    pub created_by: Option<i32>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl GDResponse for Event {
+    fn to_gd(&self) -> String {
+        let response = [
+            format!("1:{}", self.id),
+            format!("2:{}", self.number),
+            format!("3:{}", self.duration),
+            format!("4:{}", self.rewards),
+        ];
+
+        response.join(":")
+    }
+}
+
+impl Event {
+    pub fn ends_at(&self, starts_at: DateTime<Utc>) -> DateTime<Utc> {
+        starts_at + Duration::seconds(self.duration as i64)
+    }
+
+    /// Returns whether `rewards` is a list of item type and amount pairs that fits in the database.
+    pub fn is_valid_rewards(rewards: &str) -> bool {
+        if rewards.is_empty() {
+            return true;
+        }
+
+        if rewards.len() > 255 {
+            return false;
+        }
+
+        let values: Vec<&str> = rewards.split(',').collect();
+
+        values.len().is_multiple_of(2) && values.iter().all(|value| value.parse::<u32>().is_ok())
+    }
+
+    pub async fn get(pool: &PgPool, event_id: i32) -> Result<Option<Self>> {
+        let event = sqlx::query_as!(Self, "SELECT * FROM events WHERE id = $1", event_id)
+            .fetch_optional(pool)
+            .await?;
+
+        Ok(event)
+    }
+
+    pub async fn create(
+        pool: &PgPool,
+        number: i32,
+        duration: i32,
+        rewards: &str,
+        created_by: i32,
+    ) -> Result<i32> {
+        let event_id = sqlx::query_scalar!(
+            r#"
+            INSERT INTO events (number, duration, rewards, created_by)
+            VALUES ($1, $2, $3, $4)
+            RETURNING id
+            "#,
+            number,
+            duration,
+            rewards,
+            created_by
+        )
+        .fetch_one(pool)
+        .await?;
+
+        Ok(event_id)
+    }
+}
@@ -0,0 +1,88 @@
+use anyhow::Result;
+use sqlx::{PgPool, Postgres, query_builder::QueryBuilder};
+
+/// A like or dislike of a level (1), comment (2), post (3) or list (4).
+///
+/// `likes` on the item is the net score the client displays, so a like adds 1 and a dislike
+/// removes 1. Levels also count their dislikes in `dislikes`.
+pub struct ItemLike;
+
+#[derive(Debug, PartialEq)]
+pub enum Vote {
+    New,
+    Switched,
+    Unchanged,
+}
+
+impl ItemLike {
+    /// Records a vote and updates the item's counters.
+    pub async fn vote(
+        pool: &PgPool,
+        user_id: i32,
+        item_type: i16,
+        item_id: i32,
+        is_like: bool,
+    ) -> Result<Vote> {
+        let table = match item_type {
+            1 => "levels",
+            2 => "comments",
+            3 => "posts",
+            4 => "lists",
+            _ => anyhow::bail!("unknown item type {item_type}"),
+        };
+
+        let mut tx = pool.begin().await?;
+
+        // Only returns a row if the vote is new or differs from the previous one
+        let inserted = sqlx::query_scalar!(
+            r#"
+            INSERT INTO item_likes (user_id, item_type, item_id, is_like)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (user_id, item_type, item_id) DO UPDATE
+            SET is_like = EXCLUDED.is_like, created_at = NOW()
+            WHERE item_likes.is_like <> EXCLUDED.is_like
+            RETURNING xmax = 0 AS "inserted!"
+            "#,
+            user_id,
+            item_type,
+            item_id,
+            is_like
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let vote = match inserted {
+            None => return Ok(Vote::Unchanged),
+            Some(true) => Vote::New,
+            Some(false) => Vote::Switched,
+        };
+
+        let weight = if vote == Vote::Switched { 2 } else { 1 };
+        let likes = if is_like { weight } else { -weight };
+
+        let mut query: QueryBuilder<Postgres> = QueryBuilder::new("UPDATE ");
+        query.push(table);
+        query.push(" SET likes = likes + ");
+        query.push_bind(likes);
+
+        if table == "levels" {
+            let dislikes = match (is_like, &vote) {
+                (false, _) => 1,
+                (true, Vote::Switched) => -1,
+                (true, _) => 0,
+            };
+
+            query.push(", dislikes = dislikes + ");
+            query.push_bind(dislikes);
+        }
+
+        query.push(" WHERE id = ");
+        query.push_bind(item_id);
+
+        query.build().execute(&mut *tx).await?;
+
+        tx.commit().await?;
+
+        Ok(vote)
+    }
+}
@@ -2,7 +2,7 @@ use anyhow::Result;
 use chrono::{DateTime, Utc};
 use chrono_humanize::HumanTime;
 use serde::Serialize;
-use sqlx::{PgPool, prelude::FromRow};
+use sqlx::{PgConnection, PgPool, prelude::FromRow};
 
 use crate::GDResponse;
 
@@ -52,6 +52,9 @@ pub struct Level {
     pub daily_number: i16,
     pub epic_rating: i16,
     pub verification_time: i32,
+    pub platformer: bool,
+    /// Hidden from searches until a moderator reviews the level's reports.
+    pub hidden: bool,
 }
 
 impl GDResponse for Level {
@@ -117,6 +120,27 @@ impl Level {
         Ok(level)
     }
 
+    pub async fn find(pool: &PgPool, level_id: i32) -> Result<Option<Self>> {
+        let level = sqlx::query_as!(Self, "SELECT * FROM levels WHERE id = $1", level_id)
+            .fetch_optional(pool)
+            .await?;
+
+        Ok(level)
+    }
+
+    /// Locks a level until the end of the transaction and returns its current version.
+    /// Updates take this lock so each of them saves a different version.
+    pub async fn lock_version(tx: &mut PgConnection, level_id: i32) -> Result<i32> {
+        let version = sqlx::query_scalar!(
+            "SELECT version FROM levels WHERE id = $1 FOR UPDATE",
+            level_id
+        )
+        .fetch_one(tx)
+        .await?;
+
+        Ok(version)
+    }
+
     pub async fn get_by_name(pool: &PgPool, search: &str) -> Result<Vec<Self>> {
         let levels = sqlx::query_as!(
             Self,
@@ -129,9 +153,10 @@ impl Level {
         Ok(levels)
     }
 
-    pub async fn update_downloads(pool: &PgPool, level_id: i32) -> Result<()> {
+    pub async fn set_hidden(pool: &PgPool, level_id: i32, hidden: bool) -> Result<()> {
         sqlx::query!(
-            "UPDATE levels SET downloads = downloads + 1 WHERE id = $1",
+            "UPDATE levels SET hidden = $1 WHERE id = $2",
+            hidden,
             level_id
         )
         .execute(pool)
@@ -140,8 +165,174 @@ impl Level {
         Ok(())
     }
 
-    pub async fn delete(pool: &PgPool, user_id: i32, level_id: i32) -> Result<()> {
+    /// Counts a download of the level, once per account or, for guests, once per IP.
+    /// Returns `false` if the download was already counted.
+    pub async fn record_download(
+        pool: &PgPool,
+        level_id: i32,
+        user_id: Option<i32>,
+        ip: &str,
+    ) -> Result<bool> {
+        let ip = if user_id.is_some() { None } else { Some(ip) };
+
+        let mut tx = pool.begin().await?;
+
+        let result = sqlx::query!(
+            r#"
+            INSERT INTO level_downloads (level_id, user_id, ip)
+            VALUES ($1, $2, $3)
+            ON CONFLICT DO NOTHING
+            "#,
+            level_id,
+            user_id,
+            ip
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Ok(false);
+        }
+
         sqlx::query!(
+            "UPDATE levels SET downloads = downloads + 1 WHERE id = $1",
+            level_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO level_daily_downloads (level_id, day, downloads)
+            VALUES ($1, CURRENT_DATE, 1)
+            ON CONFLICT (level_id, day) DO UPDATE
+            SET downloads = level_daily_downloads.downloads + 1
+            "#,
+            level_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(true)
+    }
+
+    /// Rates a level with the given stars. `feature` is the suggestion tier
+    /// (0 = star, 1 = feature, 2 = epic, 3 = legendary, 4 = mythic), `None` keeps the current one.
+    /// Returns the ID of the level's creator, or `None` if the level doesn't exist.
+    pub async fn rate(
+        pool: &PgPool,
+        level_id: i32,
+        stars: i16,
+        feature: Option<i16>,
+    ) -> Result<Option<i32>> {
+        let auto = stars == 1;
+        let demon = stars == 10;
+
+        let difficulty: i16 = match stars {
+            1 => -3,
+            2 => 1,
+            3 => 2,
+            4 | 5 => 3,
+            6 | 7 => 4,
+            8 | 9 => 5,
+            _ => -2,
+        };
+
+        let user_id = sqlx::query_scalar!(
+            r#"
+            UPDATE levels
+            SET
+                rated = TRUE,
+                stars = $2,
+                difficulty = $3,
+                auto = $4,
+                demon = $5,
+                featured = COALESCE($6::SMALLINT >= 1, featured),
+                feature_score = CASE
+                    WHEN $6 IS NULL THEN feature_score
+                    WHEN $6 >= 1 THEN GREATEST(feature_score, 1)
+                    ELSE 0
+                END,
+                epic_rating = CASE
+                    WHEN $6 IS NULL THEN epic_rating
+                    ELSE GREATEST($6 - 1, 0)
+                END,
+                rated_at = NOW()
+            WHERE id = $1
+            RETURNING user_id
+            "#,
+            level_id,
+            stars,
+            difficulty,
+            auto,
+            demon,
+            feature
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(user_id)
+    }
+
+    /// Sets the demon difficulty from the client's 1-5 rating (easy to extreme).
+    pub async fn rate_demon(pool: &PgPool, level_id: i32, rating: i16) -> Result<()> {
+        let demon_difficulty: i16 = match rating {
+            1 => 3,
+            2 => 4,
+            4 => 5,
+            5 => 6,
+            _ => 0,
+        };
+
+        sqlx::query!(
+            "UPDATE levels SET demon_difficulty = $1 WHERE id = $2",
+            demon_difficulty,
+            level_id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Counts the rated demons among the given levels by difficulty, in profile order:
+    /// easy, medium, hard, insane and extreme classic demons, then the same for platformer ones.
+    pub async fn count_demons(pool: &PgPool, level_ids: &[i32]) -> Result<[i32; 10]> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT demon_difficulty, platformer, COUNT(*)::INT AS "count!"
+            FROM levels
+            WHERE id = ANY($1) AND rated AND demon
+            GROUP BY demon_difficulty, platformer
+            "#,
+            level_ids
+        )
+        .fetch_all(pool)
+        .await?;
+
+        let mut counts = [0; 10];
+
+        for row in rows {
+            let index = match row.demon_difficulty {
+                3 => 0,
+                4 => 1,
+                5 => 3,
+                6 => 4,
+                _ => 2,
+            };
+            let offset = if row.platformer { 5 } else { 0 };
+
+            counts[index + offset] += row.count;
+        }
+
+        Ok(counts)
+    }
+
+    /// Returns `true` if the level existed and belonged to the user.
+    pub async fn delete(pool: &PgPool, user_id: i32, level_id: i32) -> Result<bool> {
+        let result = sqlx::query!(
             "DELETE FROM levels WHERE user_id = $1 AND id = $2",
             user_id,
             level_id,
@@ -149,6 +340,72 @@ impl Level {
         .execute(pool)
         .await?;
 
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+#[derive(Debug, FromRow, Serialize)]
+pub struct LevelVersion {
+    pub id: i32,
+    pub level_id: i32,
+    pub version: i32,
+    pub level_string: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl GDResponse for LevelVersion {
+    fn to_gd(&self) -> String {
+        let response = [
+            format!("1:{}", self.level_id),
+            format!("2:{}", self.version),
+            format!("3:{}", HumanTime::from(self.created_at)).replace(" ago", ""),
+        ];
+
+        response.join(":")
+    }
+}
+
+impl LevelVersion {
+    pub async fn get(pool: &PgPool, level_id: i32, version: i32) -> Result<Option<Self>> {
+        let level_version = sqlx::query_as!(
+            Self,
+            "SELECT * FROM level_versions WHERE level_id = $1 AND version = $2",
+            level_id,
+            version
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(level_version)
+    }
+
+    pub async fn get_all(pool: &PgPool, level_id: i32) -> Result<Vec<Self>> {
+        let level_versions = sqlx::query_as!(
+            Self,
+            "SELECT * FROM level_versions WHERE level_id = $1 ORDER BY version DESC",
+            level_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(level_versions)
+    }
+
+    pub async fn create(
+        tx: &mut PgConnection,
+        level_id: i32,
+        version: i32,
+        level_string: &str,
+    ) -> Result<()> {
+        sqlx::query!(
+            "INSERT INTO level_versions (level_id, version, level_string) VALUES ($1, $2, $3)",
+            level_id,
+            version,
+            level_string
+        )
+        .execute(tx)
+        .await?;
+
         Ok(())
     }
 }
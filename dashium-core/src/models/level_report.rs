@@ -0,0 +1,101 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use chrono_humanize::HumanTime;
+use serde::Serialize;
+use sqlx::{PgPool, prelude::FromRow};
+
+use crate::GDResponse;
+
+/// The pending reports of a level.
+#[derive(Debug, FromRow, Serialize)]
+pub struct LevelReport {
+    pub level_id: i32,
+    pub level_name: String,
+    pub reports: i64,
+    pub hidden: bool,
+    pub last_reported_at: DateTime<Utc>,
+}
+
+impl GDResponse for LevelReport {
+    fn to_gd(&self) -> String {
+        let hidden = if self.hidden { 1 } else { 0 };
+
+        let response = [
+            format!("1:{}", self.level_id),
+            format!("2:{}", self.level_name),
+            format!("3:{}", self.reports),
+            format!("4:{hidden}"),
+            format!("5:{}", HumanTime::from(self.last_reported_at)).replace(" ago", ""),
+        ];
+
+        response.join(":")
+    }
+}
+
+impl LevelReport {
+    /// Returns the reported levels, most reported first.
+    pub async fn get_all(pool: &PgPool) -> Result<Vec<Self>> {
+        let reports = sqlx::query_as!(
+            Self,
+            r#"
+            SELECT
+                r.level_id,
+                l.level_name,
+                COUNT(*) AS "reports!",
+                l.hidden,
+                MAX(r.created_at) AS "last_reported_at!"
+            FROM level_reports r
+            JOIN levels l ON l.id = r.level_id
+            GROUP BY r.level_id, l.level_name, l.hidden
+            ORDER BY COUNT(*) DESC, MAX(r.created_at) DESC
+            LIMIT 100
+            "#
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(reports)
+    }
+
+    /// Reports a level, once per account or, for guests, once per IP.
+    /// Returns the number of reports the level now has.
+    pub async fn create(
+        pool: &PgPool,
+        level_id: i32,
+        user_id: Option<i32>,
+        ip: &str,
+    ) -> Result<i64> {
+        let ip = if user_id.is_some() { None } else { Some(ip) };
+
+        sqlx::query!(
+            r#"
+            INSERT INTO level_reports (level_id, user_id, ip)
+            VALUES ($1, $2, $3)
+            ON CONFLICT DO NOTHING
+            "#,
+            level_id,
+            user_id,
+            ip
+        )
+        .execute(pool)
+        .await?;
+
+        let count = sqlx::query_scalar!(
+            r#"SELECT COUNT(*) AS "count!" FROM level_reports WHERE level_id = $1"#,
+            level_id
+        )
+        .fetch_one(pool)
+        .await?;
+
+        Ok(count)
+    }
+
+    /// Removes every report of a level once it has been reviewed.
+    pub async fn clear(pool: &PgPool, level_id: i32) -> Result<()> {
+        sqlx::query!("DELETE FROM level_reports WHERE level_id = $1", level_id)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+}
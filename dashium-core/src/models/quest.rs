@@ -0,0 +1,281 @@
+use anyhow::Result;
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
+use serde::Serialize;
+use sqlx::{PgPool, prelude::FromRow};
+
+use crate::GDResponse;
+
+/// Number of quests the client shows at once.
+const QUEST_SLOTS: usize = 3;
+
+/// A quest in the pool the daily quests are picked from.
+#[derive(Debug, FromRow, Serialize)]
+pub struct Quest {
+    pub id: i32,
+    /// 1 for orbs, 2 for coins and 3 for stars.
+    pub quest_type: i16,
+    pub amount: i32,
+    /// Diamonds given on completion.
+    pub reward: i32,
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Quest {
+    pub async fn get_all(pool: &PgPool) -> Result<Vec<Self>> {
+        let quests = sqlx::query_as!(Self, "SELECT * FROM quests ORDER BY id ASC")
+            .fetch_all(pool)
+            .await?;
+
+        Ok(quests)
+    }
+
+    pub async fn create(
+        pool: &PgPool,
+        quest_type: i16,
+        amount: i32,
+        reward: i32,
+        name: &str,
+    ) -> Result<i32> {
+        let quest_id = sqlx::query_scalar!(
+            "INSERT INTO quests (quest_type, amount, reward, name) VALUES ($1, $2, $3, $4) RETURNING id",
+            quest_type,
+            amount,
+            reward,
+            name
+        )
+        .fetch_one(pool)
+        .await?;
+
+        Ok(quest_id)
+    }
+
+    /// Picks the quests of a player for a day, one of each type when the pool has some.
+    /// `seed` spreads players over the pool, and the same player always gets the same quests
+    /// on the same day as long as the pool doesn't change.
+    fn pick(quests: &[Self], day: NaiveDate, seed: usize) -> Vec<(i16, i32)> {
+        let day = (day.num_days_from_ce() as usize).wrapping_add(seed);
+
+        (0..QUEST_SLOTS)
+            .filter_map(|slot| {
+                let quest_type = slot as i16 + 1;
+                let of_type: Vec<&Self> = quests
+                    .iter()
+                    .filter(|quest| quest.quest_type == quest_type)
+                    .collect();
+
+                let quest = if of_type.is_empty() {
+                    quests.get((day * QUEST_SLOTS + slot) % quests.len().max(1))?
+                } else {
+                    of_type[day % of_type.len()]
+                };
+
+                Some((slot as i16, quest.id))
+            })
+            .collect()
+    }
+}
+
+/// A quest handed out on a given day. The client only sees the ID of the issued quest,
+/// so a quest coming back on another day isn't mistaken for one already completed.
+/// Players given the same quest in the same slot on the same day share the issued ID.
+#[derive(Debug, FromRow, Serialize)]
+pub struct IssuedQuest {
+    pub id: i32,
+    pub quest_id: i32,
+    pub day: NaiveDate,
+    pub slot: i16,
+    pub quest_type: i16,
+    pub amount: i32,
+    pub reward: i32,
+    pub name: String,
+}
+
+impl GDResponse for IssuedQuest {
+    fn to_gd(&self) -> String {
+        let response = [
+            self.id.to_string(),
+            self.quest_type.to_string(),
+            self.amount.to_string(),
+            self.reward.to_string(),
+            self.name.clone(),
+        ];
+
+        response.join(",")
+    }
+}
+
+impl IssuedQuest {
+    /// Returns the quests of an account, or of a device if `user_id` is `None`, for a day.
+    /// They are issued first if that hasn't been done yet, and then stay the same for the day.
+    pub async fn get_or_issue(
+        pool: &PgPool,
+        user_id: Option<i32>,
+        udid: &str,
+        day: NaiveDate,
+    ) -> Result<Vec<Self>> {
+        let seed = match user_id {
+            Some(user_id) => user_id as usize,
+            None => udid.bytes().fold(0, |hash: usize, byte| {
+                hash.wrapping_mul(31).wrapping_add(byte as usize)
+            }),
+        };
+
+        let udid = if user_id.is_some() { None } else { Some(udid) };
+
+        let issued = Self::get_all(pool, user_id, udid, day).await?;
+
+        if issued.len() == QUEST_SLOTS {
+            return Ok(issued);
+        }
+
+        let quests = Quest::get_all(pool).await?;
+
+        for (slot, quest_id) in Quest::pick(&quests, day, seed) {
+            // Updating on conflict makes the ID come back whether it was just issued or not
+            let issued_quest_id = sqlx::query_scalar!(
+                r#"
+                INSERT INTO issued_quests (quest_id, day, slot)
+                VALUES ($1, $2, $3)
+                ON CONFLICT (day, slot, quest_id) DO UPDATE SET day = EXCLUDED.day
+                RETURNING id
+                "#,
+                quest_id,
+                day,
+                slot
+            )
+            .fetch_one(pool)
+            .await?;
+
+            sqlx::query!(
+                r#"
+                INSERT INTO player_quests (user_id, udid, day, slot, issued_quest_id)
+                VALUES ($1, $2, $3, $4, $5)
+                ON CONFLICT (user_id, udid, day, slot) DO NOTHING
+                "#,
+                user_id,
+                udid,
+                day,
+                slot,
+                issued_quest_id
+            )
+            .execute(pool)
+            .await?;
+        }
+
+        Self::get_all(pool, user_id, udid, day).await
+    }
+
+    async fn get_all(
+        pool: &PgPool,
+        user_id: Option<i32>,
+        udid: Option<&str>,
+        day: NaiveDate,
+    ) -> Result<Vec<Self>> {
+        let issued = sqlx::query_as!(
+            Self,
+            r#"
+            SELECT
+                i.id,
+                i.quest_id,
+                i.day,
+                i.slot,
+                q.quest_type,
+                q.amount,
+                q.reward,
+                q.name
+            FROM player_quests p
+            JOIN issued_quests i ON i.id = p.issued_quest_id
+            JOIN quests q ON q.id = i.quest_id
+            WHERE p.user_id IS NOT DISTINCT FROM $1
+            AND p.udid IS NOT DISTINCT FROM $2
+            AND p.day = $3
+            ORDER BY p.slot ASC
+            "#,
+            user_id,
+            udid,
+            day
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(issued)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quest(id: i32, quest_type: i16) -> Quest {
+        Quest {
+            id,
+            quest_type,
+            amount: 10,
+            reward: 10,
+            name: format!("Quest {id}"),
+            created_at: DateTime::UNIX_EPOCH,
+        }
+    }
+
+    fn day(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 1, day).unwrap()
+    }
+
+    #[test]
+    fn picks_one_quest_of_each_type() {
+        let quests = [quest(1, 3), quest(2, 1), quest(3, 2), quest(4, 1)];
+
+        for seed in 0..4 {
+            let picked = Quest::pick(&quests, day(1), seed);
+            let types: Vec<i16> = picked
+                .iter()
+                .map(|(_, id)| quests.iter().find(|q| q.id == *id).unwrap().quest_type)
+                .collect();
+
+            assert_eq!(
+                picked.iter().map(|(slot, _)| *slot).collect::<Vec<_>>(),
+                [0, 1, 2]
+            );
+            assert_eq!(types, [1, 2, 3]);
+        }
+    }
+
+    #[test]
+    fn picks_the_same_quests_for_the_same_day_and_player() {
+        let quests = [
+            quest(1, 1),
+            quest(2, 1),
+            quest(3, 1),
+            quest(4, 2),
+            quest(5, 3),
+        ];
+
+        assert_eq!(
+            Quest::pick(&quests, day(1), 7),
+            Quest::pick(&quests, day(1), 7)
+        );
+        assert_ne!(
+            Quest::pick(&quests, day(1), 7),
+            Quest::pick(&quests, day(2), 7)
+        );
+        assert_ne!(
+            Quest::pick(&quests, day(1), 7),
+            Quest::pick(&quests, day(1), 8)
+        );
+    }
+
+    #[test]
+    fn fills_missing_types_from_the_whole_pool() {
+        let quests = [quest(1, 1), quest(2, 1)];
+        let picked = Quest::pick(&quests, day(1), 0);
+
+        assert_eq!(picked.len(), 3);
+        assert!(picked.iter().all(|(_, id)| *id == 1 || *id == 2));
+    }
+
+    #[test]
+    fn picks_nothing_from_an_empty_pool() {
+        assert!(Quest::pick(&[], day(1), 0).is_empty());
+    }
+}
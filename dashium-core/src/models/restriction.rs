@@ -0,0 +1,197 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use chrono_humanize::HumanTime;
+use serde::Serialize;
+use sqlx::{PgPool, prelude::FromRow};
+
+use crate::{GDResponse, util::base64_encode};
+
+/// Restrictions meant to last longer than this, about a hundred years, are made permanent.
+const MAX_DURATION: i64 = 100 * 365 * 24 * 60 * 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RestrictionKind {
+    /// Hidden from the leaderboards.
+    Leaderboard,
+    /// Can't upload levels.
+    Creator,
+    /// Can't post level or profile comments.
+    Comment,
+    /// Can't send messages.
+    Message,
+    /// Can't use the account at all.
+    Account,
+}
+
+impl RestrictionKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Leaderboard => "leaderboard",
+            Self::Creator => "creator",
+            Self::Comment => "comment",
+            Self::Message => "message",
+            Self::Account => "account",
+        }
+    }
+
+    pub fn parse(kind: &str) -> Option<Self> {
+        match kind {
+            "leaderboard" => Some(Self::Leaderboard),
+            "creator" => Some(Self::Creator),
+            "comment" => Some(Self::Comment),
+            "message" => Some(Self::Message),
+            "account" => Some(Self::Account),
+            _ => None,
+        }
+    }
+}
+
+/// A ban from part of the server, either permanent or until `expires_at`.
+#[derive(Debug, FromRow, Serialize)]
+pub struct Restriction {
+    pub id: i32,
+    pub user_id: i32,
+    pub kind: String,
+    pub reason: String,
+    pub moderator_id: Option<i32>,
+    pub lifted: bool,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl GDResponse for Restriction {
+    fn to_gd(&self) -> String {
+        let active = if self.is_active() { 1 } else { 0 };
+
+        let response = [
+            format!("1:{}", self.id),
+            format!("2:{}", self.kind),
+            format!("3:{}", base64_encode(&self.reason)),
+            format!("4:{}", self.moderator_id.unwrap_or(0)),
+            format!("5:{}", self.remaining().unwrap_or(0)),
+            format!("6:{active}"),
+            format!("7:{}", HumanTime::from(self.created_at)).replace(" ago", ""),
+        ];
+
+        response.join(":")
+    }
+}
+
+impl Restriction {
+    pub fn is_active(&self) -> bool {
+        !self.lifted
+            && self
+                .expires_at
+                .is_none_or(|expires_at| expires_at > Utc::now())
+    }
+
+    /// Returns the number of seconds left, or `None` if the restriction is permanent.
+    pub fn remaining(&self) -> Option<i64> {
+        self.expires_at
+            .map(|expires_at| (expires_at - Utc::now()).num_seconds().max(0))
+    }
+
+    /// The reply the client expects when a banned user tries to comment.
+    pub fn comment_ban_response(&self) -> String {
+        match self.remaining() {
+            Some(remaining) => format!("temp_{remaining}_{}", self.reason),
+            None => "-10".to_string(),
+        }
+    }
+
+    /// Returns every restriction a user ever got, latest first.
+    pub async fn get_all(pool: &PgPool, user_id: i32) -> Result<Vec<Self>> {
+        let restrictions = sqlx::query_as!(
+            Self,
+            "SELECT * FROM restrictions WHERE user_id = $1 ORDER BY id DESC LIMIT 100",
+            user_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(restrictions)
+    }
+
+    /// Returns the longest running restriction of a kind a user is currently under.
+    pub async fn get_active(
+        pool: &PgPool,
+        user_id: i32,
+        kind: RestrictionKind,
+    ) -> Result<Option<Self>> {
+        // Columns of views are all nullable as far as sqlx knows
+        let restriction = sqlx::query_as!(
+            Self,
+            r#"
+            SELECT
+                id AS "id!",
+                user_id AS "user_id!",
+                kind AS "kind!",
+                reason AS "reason!",
+                moderator_id,
+                lifted AS "lifted!",
+                expires_at,
+                created_at AS "created_at!"
+            FROM active_restrictions
+            WHERE user_id = $1 AND kind = $2
+            ORDER BY expires_at DESC NULLS FIRST
+            LIMIT 1
+            "#,
+            user_id,
+            kind.as_str()
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(restriction)
+    }
+
+    pub async fn is_restricted(pool: &PgPool, user_id: i32, kind: RestrictionKind) -> Result<bool> {
+        Ok(Self::get_active(pool, user_id, kind).await?.is_some())
+    }
+
+    /// Restricts a user for `duration` seconds, or permanently if `None` or absurdly long.
+    pub async fn create(
+        pool: &PgPool,
+        user_id: i32,
+        kind: RestrictionKind,
+        reason: &str,
+        moderator_id: Option<i32>,
+        duration: Option<i64>,
+    ) -> Result<i32> {
+        let duration = duration.filter(|duration| *duration <= MAX_DURATION);
+
+        let restriction_id = sqlx::query_scalar!(
+            r#"
+            INSERT INTO restrictions (user_id, kind, reason, moderator_id, expires_at)
+            VALUES ($1, $2, $3, $4, NOW() + make_interval(secs => $5))
+            RETURNING id
+            "#,
+            user_id,
+            kind.as_str(),
+            reason,
+            moderator_id,
+            duration.map(|duration| duration as f64)
+        )
+        .fetch_one(pool)
+        .await?;
+
+        Ok(restriction_id)
+    }
+
+    /// Lifts a restriction. Returns `false` if it was not active anymore.
+    pub async fn lift(pool: &PgPool, restriction_id: i32) -> Result<bool> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE restrictions SET lifted = TRUE
+            WHERE id = $1
+            AND NOT lifted
+            AND (expires_at IS NULL OR expires_at > NOW())
+            "#,
+            restriction_id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
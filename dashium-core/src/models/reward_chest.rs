@@ -0,0 +1,131 @@
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use sqlx::{PgPool, prelude::FromRow};
+
+use crate::config::{ChestRewards, config};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChestKind {
+    Small,
+    Large,
+}
+
+impl ChestKind {
+    /// Seconds between two openings.
+    pub fn wait(&self) -> i64 {
+        match self {
+            Self::Small => config().small_chest_wait,
+            Self::Large => config().large_chest_wait,
+        }
+    }
+
+    pub fn rewards(&self) -> &'static ChestRewards {
+        match self {
+            Self::Small => &config().small_chest,
+            Self::Large => &config().large_chest,
+        }
+    }
+}
+
+/// The chest timers of an account, or of a device for players without one.
+#[derive(Debug, FromRow, Serialize)]
+pub struct RewardChests {
+    pub id: i32,
+    pub user_id: Option<i32>,
+    pub udid: Option<String>,
+    pub small_count: i32,
+    pub small_opened_at: Option<DateTime<Utc>>,
+    pub large_count: i32,
+    pub large_opened_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl RewardChests {
+    /// Returns the chests of an account, or of a device if `user_id` is `None`.
+    pub async fn get(pool: &PgPool, user_id: Option<i32>, udid: &str) -> Result<Self> {
+        let udid = if user_id.is_some() { None } else { Some(udid) };
+
+        // Updating on conflict makes the row come back whether it was just created or not
+        let chests = sqlx::query_as!(
+            Self,
+            r#"
+            INSERT INTO reward_chests (user_id, udid)
+            VALUES ($1, $2)
+            ON CONFLICT (user_id, udid) DO UPDATE SET user_id = EXCLUDED.user_id
+            RETURNING *
+            "#,
+            user_id,
+            udid
+        )
+        .fetch_one(pool)
+        .await?;
+
+        Ok(chests)
+    }
+
+    pub fn count(&self, kind: ChestKind) -> i32 {
+        match kind {
+            ChestKind::Small => self.small_count,
+            ChestKind::Large => self.large_count,
+        }
+    }
+
+    /// Returns the number of seconds until a chest can be opened again.
+    pub fn time_left(&self, kind: ChestKind) -> i64 {
+        let opened_at = match kind {
+            ChestKind::Small => self.small_opened_at,
+            ChestKind::Large => self.large_opened_at,
+        };
+
+        opened_at.map_or(0, |opened_at| {
+            (opened_at + Duration::seconds(kind.wait()) - Utc::now())
+                .num_seconds()
+                .max(0)
+        })
+    }
+
+    /// Opens a chest. Returns `false` if it is not ready yet.
+    pub async fn open(&mut self, pool: &PgPool, kind: ChestKind) -> Result<bool> {
+        let wait = kind.wait() as f64;
+
+        let opened = match kind {
+            ChestKind::Small => sqlx::query_as!(
+                Self,
+                r#"
+                UPDATE reward_chests
+                SET small_count = small_count + 1, small_opened_at = NOW()
+                WHERE id = $1
+                AND (small_opened_at IS NULL OR small_opened_at <= NOW() - make_interval(secs => $2))
+                RETURNING *
+                "#,
+                self.id,
+                wait
+            )
+            .fetch_optional(pool)
+            .await?,
+            ChestKind::Large => sqlx::query_as!(
+                Self,
+                r#"
+                UPDATE reward_chests
+                SET large_count = large_count + 1, large_opened_at = NOW()
+                WHERE id = $1
+                AND (large_opened_at IS NULL OR large_opened_at <= NOW() - make_interval(secs => $2))
+                RETURNING *
+                "#,
+                self.id,
+                wait
+            )
+            .fetch_optional(pool)
+            .await?,
+        };
+
+        let Some(opened) = opened else {
+            return Ok(false);
+        };
+
+        *self = opened;
+
+        Ok(true)
+    }
+}
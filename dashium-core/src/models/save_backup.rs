@@ -0,0 +1,156 @@
+use std::io::{Read, Write};
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use chrono_humanize::HumanTime;
+use flate2::{Compression, read::GzDecoder, write::GzEncoder};
+use serde::Serialize;
+use sqlx::{PgPool, prelude::FromRow};
+
+use crate::{GDResponse, config::config};
+
+/// A save data backup, without the data itself.
+#[derive(Debug, FromRow, Serialize)]
+pub struct SaveBackup {
+    pub id: i32,
+    pub user_id: i32,
+    pub size: i32,
+    pub created_at: DateTime<Utc>,
+}
+
+impl GDResponse for SaveBackup {
+    fn to_gd(&self) -> String {
+        let response = [
+            format!("1:{}", self.id),
+            format!("2:{}", self.size),
+            format!("3:{}", HumanTime::from(self.created_at)).replace(" ago", ""),
+        ];
+
+        response.join(":")
+    }
+}
+
+impl SaveBackup {
+    pub async fn get_all(pool: &PgPool, user_id: i32) -> Result<Vec<Self>> {
+        let backups = sqlx::query_as!(
+            Self,
+            "SELECT id, user_id, size, created_at FROM save_backups WHERE user_id = $1 ORDER BY id DESC",
+            user_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(backups)
+    }
+
+    /// Stores a new backup and drops the ones past `SAVE_BACKUP_VERSIONS`.
+    pub async fn create(pool: &PgPool, user_id: i32, save_data: &str) -> Result<()> {
+        let size = save_data.len() as i32;
+        let save_data = save_data.to_string();
+        let compressed = tokio::task::spawn_blocking(move || {
+            let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(save_data.as_bytes())?;
+            anyhow::Ok(encoder.finish()?)
+        })
+        .await??;
+
+        let mut tx = pool.begin().await?;
+
+        sqlx::query!(
+            "INSERT INTO save_backups (user_id, data, size) VALUES ($1, $2, $3)",
+            user_id,
+            compressed,
+            size
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        Self::prune(&mut tx, user_id).await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Returns the save data of a backup, or of the latest one if `backup_id` is `None`.
+    pub async fn load(
+        pool: &PgPool,
+        user_id: i32,
+        backup_id: Option<i32>,
+    ) -> Result<Option<String>> {
+        let backup = sqlx::query!(
+            r#"
+            SELECT data, compressed FROM save_backups
+            WHERE user_id = $1 AND ($2::INT IS NULL OR id = $2)
+            ORDER BY id DESC
+            LIMIT 1
+            "#,
+            user_id,
+            backup_id
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        let Some(backup) = backup else {
+            return Ok(None);
+        };
+
+        if !backup.compressed {
+            return Ok(Some(String::from_utf8(backup.data)?));
+        }
+
+        let save_data = tokio::task::spawn_blocking(move || {
+            let mut save_data = String::new();
+            GzDecoder::new(backup.data.as_slice()).read_to_string(&mut save_data)?;
+            anyhow::Ok(save_data)
+        })
+        .await??;
+
+        Ok(Some(save_data))
+    }
+
+    /// Makes an older backup the latest one again, so the next sync downloads it.
+    /// Returns `false` if the backup doesn't belong to the user.
+    pub async fn restore(pool: &PgPool, user_id: i32, backup_id: i32) -> Result<bool> {
+        let mut tx = pool.begin().await?;
+
+        let result = sqlx::query!(
+            r#"
+            INSERT INTO save_backups (user_id, data, compressed, size)
+            SELECT user_id, data, compressed, size FROM save_backups
+            WHERE id = $1 AND user_id = $2
+            "#,
+            backup_id,
+            user_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Ok(false);
+        }
+
+        Self::prune(&mut tx, user_id).await?;
+
+        tx.commit().await?;
+
+        Ok(true)
+    }
+
+    async fn prune(tx: &mut sqlx::PgConnection, user_id: i32) -> Result<()> {
+        sqlx::query!(
+            r#"
+            DELETE FROM save_backups
+            WHERE user_id = $1 AND id NOT IN (
+                SELECT id FROM save_backups WHERE user_id = $1 ORDER BY id DESC LIMIT $2
+            )
+            "#,
+            user_id,
+            config().save_backup_versions
+        )
+        .execute(tx)
+        .await?;
+
+        Ok(())
+    }
+}
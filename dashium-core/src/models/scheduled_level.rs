@@ -0,0 +1,214 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::{PgPool, prelude::FromRow};
+
+use crate::GDResponse;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScheduleKind {
+    Daily,
+    Weekly,
+    Event,
+}
+
+impl ScheduleKind {
+    pub const ALL: [Self; 3] = [Self::Daily, Self::Weekly, Self::Event];
+
+    /// Parses the `type` sent by the client, 0 for the daily, 1 for the weekly and 2 for the event.
+    pub fn from_type(daily_type: u8) -> Option<Self> {
+        match daily_type {
+            0 => Some(Self::Daily),
+            1 => Some(Self::Weekly),
+            2 => Some(Self::Event),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Daily => "daily level",
+            Self::Weekly => "weekly demon",
+            Self::Event => "event level",
+        }
+    }
+
+    /// Added to the row IDs so the client can tell the kinds apart.
+    pub fn id_offset(&self) -> i32 {
+        match self {
+            Self::Daily => 0,
+            Self::Weekly => 100000,
+            Self::Event => 200000,
+        }
+    }
+
+    fn table(&self) -> &'static str {
+        match self {
+            Self::Daily => "daily_levels",
+            Self::Weekly => "weekly_demons",
+            Self::Event => "event_levels",
+        }
+    }
+}
+
+/// A daily level, weekly demon or event level. Levels without a start time are waiting
+/// in the queue for the next free rotation.
+#[derive(Debug, FromRow, Serialize)]
+pub struct ScheduledLevel {
+    pub id: i32,
+    pub level_id: i32,
+    pub starts_at: Option<DateTime<Utc>>,
+    pub queued_by: Option<i32>,
+    /// Only event levels have one.
+    #[sqlx(default)]
+    pub event_id: Option<i32>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl GDResponse for ScheduledLevel {
+    fn to_gd(&self) -> String {
+        let starts_at = self.starts_at.map_or(0, |starts_at| starts_at.timestamp());
+
+        let response = [
+            format!("1:{}", self.id),
+            format!("2:{}", self.level_id),
+            format!("3:{starts_at}"),
+            format!("4:{}", self.queued_by.unwrap_or(0)),
+            format!("5:{}", self.event_id.unwrap_or(0)),
+        ];
+
+        response.join(":")
+    }
+}
+
+impl ScheduledLevel {
+    /// Returns the level that started last.
+    pub async fn current(pool: &PgPool, kind: ScheduleKind) -> Result<Option<Self>> {
+        let query = format!(
+            r#"
+            SELECT * FROM {}
+            WHERE starts_at <= NOW()
+            ORDER BY starts_at DESC, id DESC
+            LIMIT 1
+            "#,
+            kind.table()
+        );
+
+        let scheduled = sqlx::query_as(&query).fetch_optional(pool).await?;
+
+        Ok(scheduled)
+    }
+
+    /// Returns the levels that have not started yet, in the order they will.
+    pub async fn get_queue(pool: &PgPool, kind: ScheduleKind) -> Result<Vec<Self>> {
+        let query = format!(
+            r#"
+            SELECT * FROM {}
+            WHERE starts_at IS NULL OR starts_at > NOW()
+            ORDER BY starts_at ASC NULLS LAST, id ASC
+            "#,
+            kind.table()
+        );
+
+        let queue = sqlx::query_as(&query).fetch_all(pool).await?;
+
+        Ok(queue)
+    }
+
+    /// Returns whether a level starts between `from` and `to`.
+    pub async fn exists_between(
+        pool: &PgPool,
+        kind: ScheduleKind,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<bool> {
+        let query = format!(
+            "SELECT EXISTS(SELECT 1 FROM {} WHERE starts_at >= $1 AND starts_at < $2)",
+            kind.table()
+        );
+
+        let exists = sqlx::query_scalar(&query)
+            .bind(from)
+            .bind(to)
+            .fetch_one(pool)
+            .await?;
+
+        Ok(exists)
+    }
+
+    /// Queues a level, either at a fixed time or for the next free rotation.
+    /// Only event levels can have an event definition.
+    pub async fn queue(
+        pool: &PgPool,
+        kind: ScheduleKind,
+        level_id: i32,
+        starts_at: Option<DateTime<Utc>>,
+        event_id: Option<i32>,
+        queued_by: i32,
+    ) -> Result<i32> {
+        let query = match event_id {
+            Some(_) => format!(
+                "INSERT INTO {} (level_id, starts_at, queued_by, event_id) VALUES ($1, $2, $3, $4) RETURNING id",
+                kind.table()
+            ),
+            None => format!(
+                "INSERT INTO {} (level_id, starts_at, queued_by) VALUES ($1, $2, $3) RETURNING id",
+                kind.table()
+            ),
+        };
+
+        let mut query = sqlx::query_scalar(&query)
+            .bind(level_id)
+            .bind(starts_at)
+            .bind(queued_by);
+
+        if let Some(event_id) = event_id {
+            query = query.bind(event_id);
+        }
+
+        let scheduled_id = query.fetch_one(pool).await?;
+
+        Ok(scheduled_id)
+    }
+
+    /// Removes a level from the queue. Returns `false` if it already started.
+    pub async fn unqueue(pool: &PgPool, kind: ScheduleKind, scheduled_id: i32) -> Result<bool> {
+        let query = format!(
+            "DELETE FROM {} WHERE id = $1 AND (starts_at IS NULL OR starts_at > NOW())",
+            kind.table()
+        );
+
+        let result = sqlx::query(&query).bind(scheduled_id).execute(pool).await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Starts the oldest level waiting in the queue at `starts_at`, if there is one.
+    pub async fn promote(
+        pool: &PgPool,
+        kind: ScheduleKind,
+        starts_at: DateTime<Utc>,
+    ) -> Result<Option<Self>> {
+        let query = format!(
+            r#"
+            UPDATE {0} SET starts_at = $1
+            WHERE id = (
+                SELECT id FROM {0}
+                WHERE starts_at IS NULL
+                ORDER BY id ASC
+                LIMIT 1
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING *
+            "#,
+            kind.table()
+        );
+
+        let promoted = sqlx::query_as(&query)
+            .bind(starts_at)
+            .fetch_optional(pool)
+            .await?;
+
+        Ok(promoted)
+    }
+}
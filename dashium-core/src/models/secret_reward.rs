@@ -0,0 +1,87 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::{PgPool, prelude::FromRow};
+
+/// A code that can be entered in the vault for rewards.
+#[derive(Debug, FromRow, Serialize)]
+pub struct SecretReward {
+    pub id: i32,
+    pub code: String,
+    /// Item types and amounts, as `type,amount,type,amount,...`.
+    pub rewards: String,
+    /// Whether each account can redeem it only once. Players without one can't redeem it at all.
+    pub single_use: bool,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl SecretReward {
+    /// Returns the reward of a code, unless it has expired.
+    pub async fn get_by_code(pool: &PgPool, code: &str) -> Result<Option<Self>> {
+        let reward = sqlx::query_as!(
+            Self,
+            r#"
+            SELECT * FROM secret_rewards
+            WHERE LOWER(code) = LOWER($1)
+            AND (expires_at IS NULL OR expires_at > NOW())
+            "#,
+            code
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(reward)
+    }
+
+    pub async fn create(
+        pool: &PgPool,
+        code: &str,
+        rewards: &str,
+        single_use: bool,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> Result<i32> {
+        let reward_id = sqlx::query_scalar!(
+            r#"
+            INSERT INTO secret_rewards (code, rewards, single_use, expires_at)
+            VALUES ($1, $2, $3, $4)
+            RETURNING id
+            "#,
+            code,
+            rewards,
+            single_use,
+            expires_at
+        )
+        .fetch_one(pool)
+        .await?;
+
+        Ok(reward_id)
+    }
+
+    /// Records a redemption by an account, or by a device if `user_id` is `None`.
+    /// Returns `false` if a single use code was already redeemed by them.
+    ///
+    /// Whether the code is single use is saved with the redemption, so making a code single use
+    /// later doesn't count the redemptions made before.
+    pub async fn redeem(&self, pool: &PgPool, user_id: Option<i32>, udid: &str) -> Result<bool> {
+        let udid = if user_id.is_some() { None } else { Some(udid) };
+
+        // The unique index on single use redemptions keeps two requests at once from both
+        // redeeming it
+        let result = sqlx::query!(
+            r#"
+            INSERT INTO secret_reward_redemptions (reward_id, user_id, udid, single_use)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (reward_id, user_id, udid) WHERE single_use DO NOTHING
+            "#,
+            self.id,
+            user_id,
+            udid,
+            self.single_use
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
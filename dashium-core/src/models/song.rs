@@ -0,0 +1,61 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::{PgPool, prelude::FromRow};
+
+use crate::{GDResponse, config::config};
+
+#[derive(Debug, FromRow, Serialize)]
+pub struct Song {
+    pub id: i32,
+    pub song_name: String,
+    pub artist_id: i32,
+    pub artist_name: String,
+    pub size: String,
+    pub hash: String,
+    pub is_disabled: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+impl GDResponse for Song {
+    fn to_gd(&self) -> String {
+        let url = format!("{}/songs/{}.mp3", config().server_url, self.id);
+        let url = urlencoding::encode(&url);
+
+        let response = [
+            format!("1~|~{}", self.id),
+            format!("2~|~{}", self.song_name),
+            format!("3~|~{}", self.artist_id),
+            format!("4~|~{}", self.artist_name),
+            format!("5~|~{}", self.size),
+            "6~|~".to_string(),
+            format!("10~|~{url}"),
+            "7~|~".to_string(),
+            "8~|~1".to_string(),
+        ];
+
+        response.join("~|~")
+    }
+}
+
+impl Song {
+    pub async fn get(pool: &PgPool, song_id: i32) -> Result<Option<Self>> {
+        let song = sqlx::query_as!(Self, "SELECT * FROM songs WHERE id = $1", song_id)
+            .fetch_optional(pool)
+            .await?;
+
+        Ok(song)
+    }
+
+    pub async fn get_many(pool: &PgPool, song_ids: &[i32]) -> Result<Vec<Self>> {
+        let songs = sqlx::query_as!(
+            Self,
+            "SELECT * FROM songs WHERE id = ANY($1) AND is_disabled = FALSE",
+            song_ids
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(songs)
+    }
+}
@@ -0,0 +1,121 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use chrono_humanize::HumanTime;
+use serde::Serialize;
+use sqlx::{PgPool, prelude::FromRow};
+
+use crate::{
+    GDResponse,
+    services::anticheat::{Stats, Verdict},
+};
+
+/// A logged `updateGJUserScore22` submission and the verdict it got.
+#[derive(Debug, FromRow, Serialize)]
+pub struct StatSubmission {
+    pub id: i32,
+    pub user_id: i32,
+    pub stars: i32,
+    pub moons: i32,
+    pub demons: i32,
+    pub diamonds: i32,
+    pub coins: i32,
+    pub user_coins: i32,
+    pub verdict: String,
+    pub reason: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl GDResponse for StatSubmission {
+    fn to_gd(&self) -> String {
+        let response = [
+            format!("1:{}", self.id),
+            format!("2:{}", self.verdict),
+            format!("3:{}", self.reason),
+            format!("4:{}", self.stars),
+            format!("5:{}", self.moons),
+            format!("6:{}", self.demons),
+            format!("7:{}", self.diamonds),
+            format!("8:{}", self.coins),
+            format!("9:{}", self.user_coins),
+            format!("10:{}", HumanTime::from(self.created_at)).replace(" ago", ""),
+        ];
+
+        response.join(":")
+    }
+}
+
+impl StatSubmission {
+    /// Returns the latest submissions of a user, optionally leaving out accepted ones.
+    pub async fn get_all(pool: &PgPool, user_id: i32, suspicious_only: bool) -> Result<Vec<Self>> {
+        let submissions = sqlx::query_as!(
+            Self,
+            r#"
+            SELECT * FROM stat_submissions
+            WHERE user_id = $1 AND (NOT $2 OR verdict <> 'accepted')
+            ORDER BY id DESC
+            LIMIT 100
+            "#,
+            user_id,
+            suspicious_only
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(submissions)
+    }
+
+    pub async fn create(
+        pool: &PgPool,
+        user_id: i32,
+        stats: &Stats,
+        verdict: &Verdict,
+    ) -> Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO stat_submissions (
+                user_id,
+                stars,
+                moons,
+                demons,
+                diamonds,
+                coins,
+                user_coins,
+                verdict,
+                reason
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            "#,
+            user_id,
+            stats.stars,
+            stats.moons,
+            stats.demons,
+            stats.diamonds,
+            stats.coins,
+            stats.user_coins,
+            verdict.as_str(),
+            verdict.reason()
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Counts the rejected submissions of a user in the last `days` days.
+    pub async fn count_rejections(pool: &PgPool, user_id: i32, days: i32) -> Result<i64> {
+        let count = sqlx::query_scalar!(
+            r#"
+            SELECT COUNT(*) AS "count!" FROM stat_submissions
+            WHERE user_id = $1
+            AND verdict = 'rejected'
+            AND created_at > NOW() - make_interval(days => $2)
+            "#,
+            user_id,
+            days
+        )
+        .fetch_one(pool)
+        .await?;
+
+        Ok(count)
+    }
+}
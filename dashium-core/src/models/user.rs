@@ -1,11 +1,13 @@
 use anyhow::Result;
 use chrono::{DateTime, Utc};
 use serde::Serialize;
-use sqlx::{FromRow, PgPool};
+use sqlx::{FromRow, PgConnection, PgPool};
 
 use crate::{
     GDResponse,
-    util::{hash_gjp2, salt_and_sha1},
+    auth::{self, password_to_gjp2},
+    config::config,
+    util::{hash_gjp2, random_token, salt_and_sha1},
 };
 
 #[derive(FromRow, Serialize)]
@@ -20,10 +22,6 @@ pub struct User {
     #[sqlx(skip)]
     pub email: String,
 
-    #[serde(skip)]
-    #[sqlx(skip)]
-    pub save_data: String,
-
     pub username: String,
     pub stars: i32,
     pub demons: i32,
@@ -149,15 +147,48 @@ impl User {
         Ok(username)
     }
 
-    pub async fn create(pool: &PgPool, username: &str, password: &str, email: &str) -> Result<()> {
-        let gjp2 = salt_and_sha1(password, "mI29fmAnxgTs");
+    pub async fn create(
+        tx: &mut PgConnection,
+        username: &str,
+        password: &str,
+        email: &str,
+        activated: bool,
+    ) -> Result<i32> {
+        let gjp2 = password_to_gjp2(password);
         let hash = hash_gjp2(&gjp2)?;
 
-        sqlx::query!(
-            "INSERT INTO users (username, hash, email) VALUES ($1, $2, $3)",
+        let user_id = sqlx::query_scalar!(
+            "INSERT INTO users (username, hash, email, activated) VALUES ($1, $2, $3, $4) RETURNING id",
             username,
             hash,
-            email
+            email,
+            activated
+        )
+        .fetch_one(tx)
+        .await?;
+
+        Ok(user_id)
+    }
+
+    /// Deletes the accounts with this username or email that weren't activated before their
+    /// activation link expired, so they can be registered again.
+    pub async fn delete_expired_unactivated(
+        pool: &PgPool,
+        username: &str,
+        email: &str,
+    ) -> Result<()> {
+        let ttl = config().activation_link_ttl;
+
+        sqlx::query!(
+            r#"
+            DELETE FROM users
+            WHERE activated = FALSE
+            AND created_at <= NOW() - make_interval(secs => $3)
+            AND (LOWER(username) = LOWER($1) OR LOWER(email) = LOWER($2))
+            "#,
+            username,
+            email,
+            ttl as f64
         )
         .execute(pool)
         .await?;
@@ -165,6 +196,26 @@ impl User {
         Ok(())
     }
 
+    pub async fn is_activated(pool: &PgPool, user_id: i32) -> Result<bool> {
+        let activated = sqlx::query_scalar!("SELECT activated FROM users WHERE id = $1", user_id)
+            .fetch_optional(pool)
+            .await?;
+
+        Ok(activated.unwrap_or(false))
+    }
+
+    /// Activates a user. Returns `false` if they were already active.
+    pub async fn activate(pool: &PgPool, user_id: i32) -> Result<bool> {
+        let result = sqlx::query!(
+            "UPDATE users SET activated = TRUE WHERE id = $1 AND activated = FALSE",
+            user_id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
     pub async fn is_username_taken(pool: &PgPool, username: &str) -> Result<bool> {
         let exists: bool = sqlx::query_scalar!(
             "SELECT EXISTS(SELECT 1 FROM users WHERE username ILIKE $1) AS \"exists!\"",
@@ -178,7 +229,7 @@ impl User {
 
     pub async fn is_email_taken(pool: &PgPool, email: &str) -> Result<bool> {
         let exists: bool = sqlx::query_scalar!(
-            "SELECT EXISTS(SELECT 1 FROM users WHERE email ILIKE $1) AS \"exists!\"",
+            "SELECT EXISTS(SELECT 1 FROM users WHERE LOWER(email) = LOWER($1)) AS \"exists!\"",
             email
         )
         .fetch_one(pool)
@@ -187,26 +238,131 @@ impl User {
         Ok(exists)
     }
 
-    pub async fn save_data(pool: &PgPool, user_id: i32, data: &str) -> Result<()> {
+    /// Returns the ID of the account an email belongs to, along with the email as stored.
+    pub async fn find_by_email(pool: &PgPool, email: &str) -> Result<Option<(i32, String)>> {
+        let user = sqlx::query!(
+            "SELECT id, email FROM users WHERE LOWER(email) = LOWER($1)",
+            email
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(user.map(|user| (user.id, user.email)))
+    }
+
+    pub async fn email_from_id(pool: &PgPool, user_id: i32) -> Result<String> {
+        let email = sqlx::query_scalar!("SELECT email FROM users WHERE id = $1", user_id)
+            .fetch_one(pool)
+            .await?;
+
+        Ok(email)
+    }
+
+    /// Sets a new password, signs the user out everywhere and drops pending reset links.
+    pub async fn update_password(pool: &PgPool, user_id: i32, password: &str) -> Result<()> {
+        let hash = hash_gjp2(&password_to_gjp2(password))?;
+
+        let mut tx = pool.begin().await?;
+
+        sqlx::query!("UPDATE users SET hash = $1 WHERE id = $2", hash, user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query!("DELETE FROM password_resets WHERE user_id = $1", user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        auth::invalidate(user_id);
+
+        Ok(())
+    }
+
+    /// Renames a user, including the copies of the username stored alongside their content.
+    pub async fn update_username(pool: &PgPool, user_id: i32, username: &str) -> Result<()> {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query!(
+            "UPDATE users SET username = $1 WHERE id = $2",
+            username,
+            user_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            "UPDATE levels SET username = $1 WHERE user_id = $2",
+            username,
+            user_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            "UPDATE lists SET username = $1 WHERE user_id = $2",
+            username,
+            user_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            "UPDATE comments SET username = $1 WHERE user_id = $2",
+            username,
+            user_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            "UPDATE posts SET username = $1 WHERE user_id = $2",
+            username,
+            user_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Creates a password reset token. Only its hash is stored.
+    pub async fn create_password_reset(pool: &PgPool, user_id: i32) -> Result<String> {
+        let token = random_token();
+        let token_hash = salt_and_sha1(&token, "");
+        let ttl = config().password_reset_ttl;
+
         sqlx::query!(
             r#"
-            UPDATE users
-            SET save_data = $1
-            WHERE id = $2
+            INSERT INTO password_resets (user_id, token_hash, expires_at)
+            VALUES ($1, $2, NOW() + make_interval(secs => $3))
             "#,
-            data,
-            user_id
+            user_id,
+            token_hash,
+            ttl as f64
         )
         .execute(pool)
         .await?;
-        Ok(())
+
+        Ok(token)
     }
 
-    pub async fn load_data(pool: &PgPool, user_id: i32) -> Result<String> {
-        let data = sqlx::query_scalar!("SELECT save_data FROM users WHERE id = $1", user_id)
-            .fetch_one(pool)
-            .await?;
+    /// Consumes a password reset token and returns the user it belongs to, if it is still valid.
+    pub async fn redeem_password_reset(pool: &PgPool, token: &str) -> Result<Option<i32>> {
+        let token_hash = salt_and_sha1(token, "");
+
+        let reset = sqlx::query!(
+            r#"
+            DELETE FROM password_resets
+            WHERE token_hash = $1
+            RETURNING user_id, expires_at > NOW() AS "is_valid!"
+            "#,
+            token_hash
+        )
+        .fetch_optional(pool)
+        .await?;
 
-        Ok(data)
+        Ok(reset.filter(|r| r.is_valid).map(|r| r.user_id))
     }
 }
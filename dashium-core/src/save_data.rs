@@ -0,0 +1,567 @@
+//! Decoder for account save data backups.
+//!
+//! The `saveData` of a backup is made of two `;`-separated parts, the CCGameManager and the
+//! CCLocalLevels file. Each part is a URL-safe Base64 encoded gzip stream. The `.dat` files on
+//! disk have an extra layer on top, every byte being XORed with `11`, so [`Plist::decode`]
+//! accepts those too. Once decompressed, a part is a plist where the tags are usually shortened
+//! (`<d>`, `<k>`, `<s>`, `<i>`, `<r>`, `<t />`, `<a>`), though the full names are accepted as well.
+
+use std::{collections::HashMap, io::Read};
+
+use anyhow::{Context, Result, bail};
+use base64::{
+    Engine as _,
+    engine::{DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig},
+};
+use flate2::read::GzDecoder;
+
+const BASE64: GeneralPurpose = GeneralPurpose::new(
+    &base64::alphabet::URL_SAFE,
+    GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
+
+/// Save files tend to compress very well, this keeps a malicious one from eating all memory.
+const MAX_DECODED_SIZE: u64 = 128 * 1024 * 1024;
+
+const XOR_KEY: u8 = 11;
+
+/// Real saves nest only a few levels deep. Parsing is recursive, so this keeps a crafted one
+/// from overflowing the stack.
+const MAX_DEPTH: usize = 64;
+
+/// A decoded save data backup.
+#[derive(Debug)]
+pub struct SaveData {
+    pub game_manager: Plist,
+    pub local_levels: Option<Plist>,
+}
+
+/// A value of a GD plist.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Plist {
+    String(String),
+    Integer(i64),
+    Real(f64),
+    Bool(bool),
+    Dict(HashMap<String, Plist>),
+    Array(Vec<Plist>),
+}
+
+/// The player's statistics (`GS_value`).
+#[derive(Debug, Default)]
+pub struct Stats {
+    pub jumps: i64,
+    pub attempts: i64,
+    pub official_levels: i64,
+    pub online_levels: i64,
+    pub demons: i64,
+    pub stars: i64,
+    pub map_packs: i64,
+    pub secret_coins: i64,
+    pub user_coins: i64,
+    pub diamonds: i64,
+    pub orbs: i64,
+    pub total_orbs: i64,
+    pub dailies: i64,
+    pub moons: i64,
+    pub diamond_shards: i64,
+}
+
+/// Icons, colors and effects the player has unlocked (`valueKeeper`).
+#[derive(Debug, Default)]
+pub struct UnlockedIcons {
+    pub cubes: Vec<i32>,
+    pub ships: Vec<i32>,
+    pub balls: Vec<i32>,
+    pub ufos: Vec<i32>,
+    pub waves: Vec<i32>,
+    pub robots: Vec<i32>,
+    pub spiders: Vec<i32>,
+    pub swings: Vec<i32>,
+    pub jetpacks: Vec<i32>,
+    pub explosions: Vec<i32>,
+    pub trails: Vec<i32>,
+    pub colors1: Vec<i32>,
+    pub colors2: Vec<i32>,
+}
+
+/// A level from the player's created levels.
+#[derive(Debug)]
+pub struct LocalLevel {
+    pub name: String,
+    /// ID of the level once uploaded, `0` otherwise.
+    pub level_id: i32,
+}
+
+impl SaveData {
+    /// Decodes the `saveData` sent to `backupGJAccountNew`.
+    pub fn decode(save_data: &str) -> Result<Self> {
+        let mut parts = save_data.split(';');
+
+        let game_manager = match parts.next() {
+            Some(part) if !part.is_empty() => Plist::decode(part)?,
+            _ => bail!("save data has no CCGameManager part"),
+        };
+
+        let local_levels = match parts.next() {
+            Some(part) if !part.is_empty() => Some(Plist::decode(part)?),
+            _ => None,
+        };
+
+        Ok(Self {
+            game_manager,
+            local_levels,
+        })
+    }
+
+    pub fn player_name(&self) -> Option<&str> {
+        self.game_manager.get("playerName")?.as_str()
+    }
+
+    pub fn stats(&self) -> Stats {
+        let stat = |id: &str| {
+            self.game_manager
+                .get("GS_value")
+                .and_then(|stats| stats.get(id))
+                .and_then(Plist::as_i64)
+                .unwrap_or_default()
+        };
+
+        Stats {
+            jumps: stat("1"),
+            attempts: stat("2"),
+            official_levels: stat("3"),
+            online_levels: stat("4"),
+            demons: stat("5"),
+            stars: stat("6"),
+            map_packs: stat("7"),
+            secret_coins: stat("8"),
+            user_coins: stat("12"),
+            diamonds: stat("13"),
+            orbs: stat("14"),
+            dailies: stat("15"),
+            total_orbs: stat("22"),
+            moons: stat("28"),
+            diamond_shards: stat("29"),
+        }
+    }
+
+    /// Returns the IDs of the online levels the player has completed (`c_{id}` in `GS_completed`).
+    pub fn completed_levels(&self) -> Vec<i32> {
+        let mut level_ids: Vec<i32> = self
+            .keys_of("GS_completed")
+            .filter_map(|key| key.strip_prefix("c_")?.parse().ok())
+            .collect();
+
+        level_ids.sort_unstable();
+        level_ids
+    }
+
+    pub fn unlocked_icons(&self) -> UnlockedIcons {
+        let mut icons = UnlockedIcons::default();
+
+        for key in self.keys_of("valueKeeper") {
+            let Some((kind, id)) = key.rsplit_once('_') else {
+                continue;
+            };
+
+            let Ok(id) = id.parse() else {
+                continue;
+            };
+
+            let list = match kind {
+                "i" => &mut icons.cubes,
+                "ship" => &mut icons.ships,
+                "ball" => &mut icons.balls,
+                "bird" => &mut icons.ufos,
+                "dart" => &mut icons.waves,
+                "robot" => &mut icons.robots,
+                "spider" => &mut icons.spiders,
+                "swing" => &mut icons.swings,
+                "jetpack" => &mut icons.jetpacks,
+                "death" => &mut icons.explosions,
+                "special" => &mut icons.trails,
+                "c0" => &mut icons.colors1,
+                "c1" => &mut icons.colors2,
+                _ => continue,
+            };
+
+            list.push(id);
+        }
+
+        for list in [
+            &mut icons.cubes,
+            &mut icons.ships,
+            &mut icons.balls,
+            &mut icons.ufos,
+            &mut icons.waves,
+            &mut icons.robots,
+            &mut icons.spiders,
+            &mut icons.swings,
+            &mut icons.jetpacks,
+            &mut icons.explosions,
+            &mut icons.trails,
+            &mut icons.colors1,
+            &mut icons.colors2,
+        ] {
+            list.sort_unstable();
+        }
+
+        icons
+    }
+
+    /// Returns the levels in the player's "Create" tab.
+    pub fn local_levels(&self) -> Vec<LocalLevel> {
+        let Some(Plist::Dict(levels)) = self.local_levels.as_ref().and_then(|ll| ll.get("LLM_01"))
+        else {
+            return Vec::new();
+        };
+
+        let mut entries: Vec<(usize, LocalLevel)> = levels
+            .iter()
+            .filter_map(|(key, level)| {
+                let index = key.strip_prefix("k_")?.parse().ok()?;
+                let level = LocalLevel {
+                    name: level.get("k2").and_then(Plist::as_str)?.to_string(),
+                    level_id: level.get("k1").and_then(Plist::as_i64).unwrap_or_default() as i32,
+                };
+                Some((index, level))
+            })
+            .collect();
+
+        entries.sort_unstable_by_key(|(index, _)| *index);
+        entries.into_iter().map(|(_, level)| level).collect()
+    }
+
+    fn keys_of(&self, key: &str) -> impl Iterator<Item = &str> {
+        let dict = match self.game_manager.get(key) {
+            Some(Plist::Dict(dict)) => Some(dict),
+            _ => None,
+        };
+
+        dict.into_iter()
+            .flat_map(|dict| dict.keys().map(String::as_str))
+    }
+}
+
+impl Plist {
+    /// Decodes a save file, either a part of a backup or the raw contents of a `.dat` file.
+    pub fn decode(file: &str) -> Result<Self> {
+        Self::parse(&decompress(file)?)
+    }
+
+    /// Parses a decompressed plist document.
+    pub fn parse(xml: &str) -> Result<Self> {
+        let mut parser = Parser {
+            input: xml,
+            pos: 0,
+            depth: 0,
+        };
+
+        loop {
+            let tag = parser.next_tag()?.context("plist has no root value")?;
+
+            if tag.closing || tag.name == "plist" {
+                continue;
+            }
+
+            return parser.value(tag);
+        }
+    }
+
+    /// Looks up a key of a dict.
+    pub fn get(&self, key: &str) -> Option<&Plist> {
+        match self {
+            Self::Dict(dict) => dict.get(key),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// Returns the value as an integer. GD stores most numbers as strings, so those are accepted.
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            Self::Integer(i) => Some(*i),
+            Self::Real(r) => Some(*r as i64),
+            Self::Bool(b) => Some(*b as i64),
+            Self::String(s) => s.parse().ok(),
+            _ => None,
+        }
+    }
+}
+
+fn decompress(file: &str) -> Result<String> {
+    let file = file.trim().trim_end_matches('\0');
+
+    // Gzip streams always start with `H4sI` once Base64 encoded
+    let encoded = if file.starts_with("H4sI") {
+        file.as_bytes().to_vec()
+    } else {
+        file.bytes().map(|b| b ^ XOR_KEY).collect()
+    };
+
+    let compressed = BASE64.decode(encoded)?;
+
+    let mut data = Vec::new();
+    GzDecoder::new(compressed.as_slice())
+        .take(MAX_DECODED_SIZE + 1)
+        .read_to_end(&mut data)?;
+
+    if data.len() as u64 > MAX_DECODED_SIZE {
+        bail!("save data exceeds {MAX_DECODED_SIZE} bytes");
+    }
+
+    Ok(String::from_utf8(data)?)
+}
+
+struct Tag<'a> {
+    name: &'a str,
+    closing: bool,
+    self_closing: bool,
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+    /// Number of dicts and arrays the parser is currently in.
+    depth: usize,
+}
+
+impl<'a> Parser<'a> {
+    /// Moves past the next element tag, skipping declarations and comments.
+    fn next_tag(&mut self) -> Result<Option<Tag<'a>>> {
+        loop {
+            let Some(start) = self.input[self.pos..].find('<') else {
+                return Ok(None);
+            };
+            let start = self.pos + start;
+
+            let end = self.input[start..]
+                .find('>')
+                .map(|end| start + end)
+                .context("unterminated tag")?;
+
+            self.pos = end + 1;
+
+            let inner = &self.input[start + 1..end];
+
+            if inner.starts_with('?') || inner.starts_with('!') {
+                continue;
+            }
+
+            let closing = inner.starts_with('/');
+            let self_closing = inner.ends_with('/');
+            let name = inner
+                .trim_start_matches('/')
+                .trim_end_matches('/')
+                .split_whitespace()
+                .next()
+                .unwrap_or_default();
+
+            return Ok(Some(Tag {
+                name,
+                closing,
+                self_closing,
+            }));
+        }
+    }
+
+    /// Reads the text up to the closing tag of the current element.
+    fn text(&mut self) -> Result<String> {
+        let end = self.input[self.pos..]
+            .find('<')
+            .map(|end| self.pos + end)
+            .context("unterminated text")?;
+
+        let text = unescape(&self.input[self.pos..end]);
+        self.pos = end;
+
+        match self.next_tag()? {
+            Some(tag) if tag.closing => Ok(text),
+            _ => bail!("expected a closing tag"),
+        }
+    }
+
+    fn value(&mut self, tag: Tag<'a>) -> Result<Plist> {
+        let text = |parser: &mut Self| {
+            if tag.self_closing {
+                Ok(String::new())
+            } else {
+                parser.text()
+            }
+        };
+
+        if self.depth >= MAX_DEPTH {
+            bail!("plist is nested deeper than {MAX_DEPTH} levels");
+        }
+
+        self.depth += 1;
+
+        let value = match tag.name {
+            "d" | "dict" => Plist::Dict(self.dict(tag.self_closing)?),
+            "a" | "array" => Plist::Array(self.array(tag.self_closing)?),
+            "s" | "string" => Plist::String(text(self)?),
+            "i" | "integer" => Plist::Integer(text(self)?.trim().parse().unwrap_or_default()),
+            "r" | "real" => Plist::Real(text(self)?.trim().parse().unwrap_or_default()),
+            "t" | "true" => Plist::Bool(true),
+            "f" | "false" => Plist::Bool(false),
+            name => bail!("unknown plist tag <{name}>"),
+        };
+
+        self.depth -= 1;
+
+        Ok(value)
+    }
+
+    fn dict(&mut self, empty: bool) -> Result<HashMap<String, Plist>> {
+        let mut dict = HashMap::new();
+
+        if empty {
+            return Ok(dict);
+        }
+
+        loop {
+            let tag = self.next_tag()?.context("unterminated dict")?;
+
+            if tag.closing {
+                return Ok(dict);
+            }
+
+            if tag.name != "k" && tag.name != "key" {
+                bail!("expected a key, found <{}>", tag.name);
+            }
+
+            let key = if tag.self_closing {
+                String::new()
+            } else {
+                self.text()?
+            };
+
+            let tag = self.next_tag()?.context("key without a value")?;
+            dict.insert(key, self.value(tag)?);
+        }
+    }
+
+    fn array(&mut self, empty: bool) -> Result<Vec<Plist>> {
+        let mut array = Vec::new();
+
+        if empty {
+            return Ok(array);
+        }
+
+        loop {
+            let tag = self.next_tag()?.context("unterminated array")?;
+
+            if tag.closing {
+                return Ok(array);
+            }
+
+            array.push(self.value(tag)?);
+        }
+    }
+}
+
+fn unescape(text: &str) -> String {
+    if !text.contains('&') {
+        return text.to_string();
+    }
+
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use flate2::{Compression, write::GzEncoder};
+
+    use super::*;
+
+    fn encode(xml: &str) -> String {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(xml.as_bytes()).unwrap();
+        BASE64.encode(encoder.finish().unwrap())
+    }
+
+    /// A plist of dicts nested `depth` levels deep.
+    fn nested(depth: usize) -> String {
+        let open = "<d><k>a</k>".repeat(depth - 1);
+        let close = "</d>".repeat(depth - 1);
+        format!("<plist>{open}<d />{close}</plist>")
+    }
+
+    #[test]
+    fn parses_a_save() {
+        let xml = r#"<?xml version="1.0"?><plist version="1.0" gjver="2.0"><dict>
+            <k>playerName</k><s>Robtop &amp; co</s>
+            <k>GS_value</k><d><k>6</k><s>120</s><k>13</k><i>42</i></d>
+            <k>valueKeeper</k><d><k>i_12</k><s>1</s></d>
+            <k>flags</k><a><t /><f /><r>1.5</r></a>
+        </dict></plist>"#;
+
+        let save = SaveData::decode(&encode(xml)).unwrap();
+
+        assert_eq!(save.player_name(), Some("Robtop & co"));
+        assert_eq!(save.stats().stars, 120);
+        assert_eq!(save.stats().diamonds, 42);
+        assert!(save.local_levels.is_none());
+        assert_eq!(
+            save.game_manager.get("flags"),
+            Some(&Plist::Array(vec![
+                Plist::Bool(true),
+                Plist::Bool(false),
+                Plist::Real(1.5)
+            ]))
+        );
+    }
+
+    #[test]
+    fn accepts_nesting_up_to_the_limit() {
+        assert!(Plist::parse(&nested(MAX_DEPTH)).is_ok());
+    }
+
+    #[test]
+    fn rejects_deep_nesting() {
+        assert!(Plist::parse(&nested(MAX_DEPTH + 1)).is_err());
+        assert!(Plist::parse(&nested(100_000)).is_err());
+        assert!(Plist::parse(&"<a>".repeat(100_000)).is_err());
+    }
+
+    #[test]
+    fn rejects_malformed_plists() {
+        let cases = [
+            "",
+            "<plist></plist>",
+            "<d><k>a</k><s>b</s>",
+            "<d><k>a</k></d>",
+            "<d><s>a</s></d>",
+            "<d><k>a</k><x>b</x></d>",
+            "<d><k>a</k><s>b",
+            "<d><k>a",
+            "<d",
+        ];
+
+        for xml in cases {
+            assert!(Plist::parse(xml).is_err(), "{xml:?} should not parse");
+        }
+    }
+
+    #[test]
+    fn rejects_malformed_save_data() {
+        assert!(SaveData::decode("").is_err());
+        assert!(SaveData::decode(";").is_err());
+        assert!(SaveData::decode("H4sI!!!").is_err());
+        assert!(SaveData::decode(&BASE64.encode(b"not gzip")).is_err());
+        assert!(SaveData::decode(&encode(&nested(MAX_DEPTH + 1))).is_err());
+    }
+}
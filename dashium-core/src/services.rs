@@ -0,0 +1,4 @@
+pub mod anticheat;
+pub mod creator_points;
+pub mod permissions;
+pub mod scheduler;
@@ -0,0 +1,291 @@
+//! Validation of the stats sent to `updateGJUserScore22`.
+//!
+//! Every submission gets one of these verdicts, all of which are logged in `stat_submissions`:
+//! - rejected: the stats are impossible, either more than rated levels and official content
+//!   can give, or sent with a bad `seed2` while `VERIFY_SEED2` is enabled. Nothing is saved.
+//! - flagged: the stats are possible, but gained suspiciously fast or sent with a bad `seed2`
+//!   while `VERIFY_SEED2` is disabled. They are saved anyway.
+//! - accepted: everything else.
+//!
+//! Users with `LEADERBOARD_BAN_THRESHOLD` rejections in the last `LEADERBOARD_BAN_WINDOW` days
+//! get a permanent leaderboard restriction, which is logged with a `banned` verdict.
+
+use std::{
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use anyhow::Result;
+use sqlx::PgPool;
+use tracing::warn;
+
+use crate::{
+    config::config,
+    models::{Restriction, RestrictionKind, StatSubmission},
+};
+
+/// Largest gain of each stat a single update can have without being flagged.
+const MAX_STAR_GAIN: i32 = 500;
+const MAX_MOON_GAIN: i32 = 150;
+const MAX_DEMON_GAIN: i32 = 20;
+const MAX_DIAMOND_GAIN: i32 = 5000;
+const MAX_COIN_GAIN: i32 = 30;
+const MAX_USER_COIN_GAIN: i32 = 100;
+
+/// How long the obtainable totals are reused before being counted again.
+const OBTAINABLE_TTL: Duration = Duration::from_secs(300);
+
+static OBTAINABLE: Mutex<Option<(Instant, Obtainable)>> = Mutex::new(None);
+
+/// The most of each stat rated levels and map packs can give, official content aside.
+#[derive(Debug, Clone, Copy)]
+struct Obtainable {
+    stars: i32,
+    moons: i32,
+    demons: i32,
+    coins: i32,
+    user_coins: i32,
+}
+
+/// The stats sent by the client.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Stats {
+    pub stars: i32,
+    pub moons: i32,
+    pub demons: i32,
+    pub diamonds: i32,
+    pub coins: i32,
+    pub user_coins: i32,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Verdict {
+    Accepted,
+    Flagged(String),
+    Rejected(String),
+    /// Logged when a user gets banned from the leaderboards.
+    Banned(String),
+}
+
+impl Verdict {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Accepted => "accepted",
+            Self::Flagged(_) => "flagged",
+            Self::Rejected(_) => "rejected",
+            Self::Banned(_) => "banned",
+        }
+    }
+
+    pub fn reason(&self) -> &str {
+        match self {
+            Self::Accepted => "",
+            Self::Flagged(reason) | Self::Rejected(reason) | Self::Banned(reason) => reason,
+        }
+    }
+}
+
+/// Judges a stat submission, logs the verdict and bans repeat offenders.
+///
+/// `valid_seed2` is whether the submission's checksum was correct.
+pub async fn review(
+    pool: &PgPool,
+    user_id: i32,
+    stats: &Stats,
+    valid_seed2: bool,
+) -> Result<Verdict> {
+    let verdict = judge(pool, user_id, stats, valid_seed2).await?;
+
+    StatSubmission::create(pool, user_id, stats, &verdict).await?;
+
+    if let Verdict::Rejected(reason) = &verdict {
+        warn!("Rejected stats of user {user_id}: {reason}");
+        ban_if_repeat_offender(pool, user_id).await?;
+    }
+
+    Ok(verdict)
+}
+
+async fn judge(pool: &PgPool, user_id: i32, stats: &Stats, valid_seed2: bool) -> Result<Verdict> {
+    let config = config();
+
+    if config.verify_seed2 && !valid_seed2 {
+        return Ok(Verdict::Rejected("invalid seed2".to_string()));
+    }
+
+    let values = [
+        stats.stars,
+        stats.moons,
+        stats.demons,
+        stats.diamonds,
+        stats.coins,
+        stats.user_coins,
+    ];
+
+    if values.iter().any(|v| *v < 0) {
+        return Ok(Verdict::Rejected("negative stats".to_string()));
+    }
+
+    // Cached totals can miss levels rated since, so they are counted again before rejecting
+    let mut exceeded = exceeded_limit(stats, &obtainable_totals(pool, false).await?);
+
+    if exceeded.is_some() {
+        exceeded = exceeded_limit(stats, &obtainable_totals(pool, true).await?);
+    }
+
+    if let Some(reason) = exceeded {
+        return Ok(Verdict::Rejected(reason));
+    }
+
+    let previous = sqlx::query_as!(
+        Stats,
+        "SELECT stars, moons, demons, diamonds, coins, user_coins FROM users WHERE id = $1",
+        user_id
+    )
+    .fetch_one(pool)
+    .await?;
+
+    let gains = [
+        ("stars", stats.stars - previous.stars, MAX_STAR_GAIN),
+        ("moons", stats.moons - previous.moons, MAX_MOON_GAIN),
+        ("demons", stats.demons - previous.demons, MAX_DEMON_GAIN),
+        (
+            "diamonds",
+            stats.diamonds - previous.diamonds,
+            MAX_DIAMOND_GAIN,
+        ),
+        ("coins", stats.coins - previous.coins, MAX_COIN_GAIN),
+        (
+            "user coins",
+            stats.user_coins - previous.user_coins,
+            MAX_USER_COIN_GAIN,
+        ),
+    ];
+
+    for (name, gain, max_gain) in gains {
+        if gain > max_gain {
+            return Ok(Verdict::Flagged(format!("gained {gain} {name} at once")));
+        }
+    }
+
+    if !valid_seed2 {
+        return Ok(Verdict::Flagged("invalid seed2".to_string()));
+    }
+
+    Ok(Verdict::Accepted)
+}
+
+/// Returns the obtainable totals, counting them again if the cached ones are too old or
+/// `refresh` is set.
+async fn obtainable_totals(pool: &PgPool, refresh: bool) -> Result<Obtainable> {
+    if !refresh
+        && let Some((counted_at, obtainable)) = *OBTAINABLE.lock().unwrap()
+        && counted_at.elapsed() < OBTAINABLE_TTL
+    {
+        return Ok(obtainable);
+    }
+
+    let levels = sqlx::query!(
+        r#"
+        SELECT
+            COALESCE(SUM(stars) FILTER (WHERE NOT platformer), 0)::INT AS "stars!",
+            COALESCE(SUM(stars) FILTER (WHERE platformer), 0)::INT AS "moons!",
+            COUNT(*) FILTER (WHERE demon)::INT AS "demons!",
+            COALESCE(SUM(coins) FILTER (WHERE verified_coins), 0)::INT AS "user_coins!"
+        FROM levels
+        WHERE rated
+        "#
+    )
+    .fetch_one(pool)
+    .await?;
+
+    let map_packs = sqlx::query!(
+        r#"
+        SELECT
+            COALESCE(SUM(stars), 0)::INT AS "stars!",
+            COALESCE(SUM(coins), 0)::INT AS "coins!"
+        FROM map_packs
+        "#
+    )
+    .fetch_one(pool)
+    .await?;
+
+    let obtainable = Obtainable {
+        stars: levels.stars + map_packs.stars,
+        moons: levels.moons,
+        demons: levels.demons,
+        coins: map_packs.coins,
+        user_coins: levels.user_coins,
+    };
+
+    *OBTAINABLE.lock().unwrap() = Some((Instant::now(), obtainable));
+
+    Ok(obtainable)
+}
+
+/// Returns why the stats are more than can be obtained, if they are.
+fn exceeded_limit(stats: &Stats, obtainable: &Obtainable) -> Option<String> {
+    let config = config();
+
+    let limits = [
+        (
+            "stars",
+            stats.stars,
+            obtainable.stars + config.official_stars,
+        ),
+        (
+            "moons",
+            stats.moons,
+            obtainable.moons + config.official_moons,
+        ),
+        (
+            "demons",
+            stats.demons,
+            obtainable.demons + config.official_demons,
+        ),
+        (
+            "coins",
+            stats.coins,
+            obtainable.coins + config.official_coins,
+        ),
+        ("user coins", stats.user_coins, obtainable.user_coins),
+    ];
+
+    limits
+        .into_iter()
+        .find(|(_, value, limit)| value > limit)
+        .map(|(name, value, limit)| format!("{value} {name} with only {limit} obtainable"))
+}
+
+async fn ban_if_repeat_offender(pool: &PgPool, user_id: i32) -> Result<()> {
+    let config = config();
+
+    let rejections =
+        StatSubmission::count_rejections(pool, user_id, config.leaderboard_ban_window).await?;
+
+    if rejections < config.leaderboard_ban_threshold {
+        return Ok(());
+    }
+
+    if Restriction::is_restricted(pool, user_id, RestrictionKind::Leaderboard).await? {
+        return Ok(());
+    }
+
+    let reason = format!("{rejections} rejected submissions");
+    Restriction::create(
+        pool,
+        user_id,
+        RestrictionKind::Leaderboard,
+        &reason,
+        None,
+        None,
+    )
+    .await?;
+
+    warn!("Banned user {user_id} from the leaderboards after {reason}");
+
+    let verdict = Verdict::Banned(reason);
+    StatSubmission::create(pool, user_id, &Stats::default(), &verdict).await?;
+
+    Ok(())
+}
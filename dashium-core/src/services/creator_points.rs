@@ -0,0 +1,59 @@
+//! Creator points are derived from a user's levels:
+//! a rated level is worth 1, featured 2, epic 3, legendary 4 and mythic 5.
+//! Being part of a gauntlet and being picked as a daily, weekly or event level
+//! are worth 1 extra point each.
+
+use anyhow::Result;
+use sqlx::PgPool;
+
+/// Recomputes the creator points of a single user.
+pub async fn update(pool: &PgPool, user_id: i32) -> Result<()> {
+    recalculate(pool, Some(user_id)).await?;
+    Ok(())
+}
+
+/// Recomputes the creator points of every user and returns how many were updated.
+pub async fn update_all(pool: &PgPool) -> Result<u64> {
+    recalculate(pool, None).await
+}
+
+async fn recalculate(pool: &PgPool, user_id: Option<i32>) -> Result<u64> {
+    let result = sqlx::query!(
+        r#"
+        UPDATE users u
+        SET creator_points = (
+            SELECT COALESCE(SUM(
+                CASE
+                    WHEN NOT l.rated THEN 0
+                    WHEN l.epic_rating > 0 THEN 2 + l.epic_rating
+                    WHEN l.featured THEN 2
+                    ELSE 1
+                END
+                + CASE WHEN EXISTS (
+                    -- Entries that aren't level IDs are skipped rather than failing the cast
+                    SELECT 1
+                    FROM gauntlets g, unnest(string_to_array(g.levels, ',')) AS entry
+                    WHERE CASE
+                        WHEN trim(entry) ~ '^[0-9]{1,9}$' THEN trim(entry)::INT
+                    END = l.id
+                ) THEN 1 ELSE 0 END
+                + CASE WHEN EXISTS (
+                    SELECT 1 FROM daily_levels d WHERE d.level_id = l.id AND d.starts_at <= NOW()
+                    UNION ALL
+                    SELECT 1 FROM weekly_demons w WHERE w.level_id = l.id AND w.starts_at <= NOW()
+                    UNION ALL
+                    SELECT 1 FROM event_levels e WHERE e.level_id = l.id AND e.starts_at <= NOW()
+                ) THEN 1 ELSE 0 END
+            ), 0)
+            FROM levels l
+            WHERE l.user_id = u.id
+        )
+        WHERE $1::INT IS NULL OR u.id = $1
+        "#,
+        user_id
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
@@ -0,0 +1,80 @@
+//! Moderator permissions are granted through roles. A user has at most one role,
+//! which also decides the badge shown next to their name and the colour of their comments.
+
+use anyhow::Result;
+use sqlx::PgPool;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Permission {
+    /// Send star suggestions for levels.
+    Suggest,
+    /// Rate levels and demon difficulties directly.
+    Rate,
+    /// Feature levels when rating them.
+    Feature,
+    /// View and roll back the version history of other users' levels.
+    EditLevels,
+    /// Delete other users' comments.
+    DeleteComments,
+    /// Review reported levels.
+    ReviewReports,
+    /// Inspect the save data and stat submissions of other users.
+    InspectAccounts,
+    /// Pick the daily, weekly and event levels.
+    SetDaily,
+    /// Restrict and ban users.
+    Ban,
+}
+
+impl Permission {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Suggest => "suggest",
+            Self::Rate => "rate",
+            Self::Feature => "feature",
+            Self::EditLevels => "edit_levels",
+            Self::DeleteComments => "delete_comments",
+            Self::ReviewReports => "review_reports",
+            Self::InspectAccounts => "inspect_accounts",
+            Self::SetDaily => "set_daily",
+            Self::Ban => "ban",
+        }
+    }
+}
+
+/// Returns whether the role of a user grants a permission.
+pub async fn has(pool: &PgPool, user_id: i32, permission: Permission) -> Result<bool> {
+    let granted = sqlx::query_scalar!(
+        r#"
+        SELECT EXISTS(
+            SELECT 1
+            FROM users
+            JOIN permissions ON permissions.role_id = users.role_id
+            WHERE users.id = $1 AND permissions.permission = $2
+        ) AS "exists!"
+        "#,
+        user_id,
+        permission.as_str()
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(granted)
+}
+
+/// Returns the moderator badge of a user, 0 if they have no role.
+pub async fn badge(pool: &PgPool, user_id: i32) -> Result<i16> {
+    let badge = sqlx::query_scalar!(
+        r#"
+        SELECT roles.badge
+        FROM users
+        JOIN roles ON roles.id = users.role_id
+        WHERE users.id = $1
+        "#,
+        user_id
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(badge.unwrap_or(0))
+}
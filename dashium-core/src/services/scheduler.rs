@@ -0,0 +1,300 @@
+//! Rotation of the daily level, weekly demon and event level.
+//!
+//! Rotations happen at `ROTATION_HOUR` UTC, every day for the daily and event levels and every
+//! Monday for the weekly demon. Levels queued with a start time simply start then. When a
+//! rotation comes without one, the oldest level queued without a start time is promoted.
+//! If the queue is empty, the previous level stays up and a warning is logged.
+//!
+//! Event levels with an event definition last for its duration instead, and the next one is
+//! promoted as soon as it ends.
+
+use std::time::Duration as StdDuration;
+
+use anyhow::Result;
+use chrono::{DateTime, Datelike, Duration, Utc};
+use sqlx::PgPool;
+use tracing::{error, info, warn};
+
+use crate::{
+    config::config,
+    models::{Event, Level, ScheduleKind, ScheduledLevel},
+    services::creator_points,
+};
+
+/// Longest time between two checks, so levels queued after an empty rotation don't wait a day.
+const POLL_INTERVAL: StdDuration = StdDuration::from_secs(300);
+
+/// Returns the start and end of the rotation `now` falls in.
+pub fn period(kind: ScheduleKind, now: DateTime<Utc>) -> (DateTime<Utc>, DateTime<Utc>) {
+    period_at(kind, now, config().rotation_hour)
+}
+
+fn period_at(
+    kind: ScheduleKind,
+    now: DateTime<Utc>,
+    rotation_hour: u32,
+) -> (DateTime<Utc>, DateTime<Utc>) {
+    let boundary = now
+        .date_naive()
+        .and_hms_opt(rotation_hour, 0, 0)
+        .unwrap()
+        .and_utc();
+
+    let mut start = if boundary <= now {
+        boundary
+    } else {
+        boundary - Duration::days(1)
+    };
+
+    match kind {
+        ScheduleKind::Weekly => {
+            start -= Duration::days(start.weekday().num_days_from_monday() as i64);
+            (start, start + Duration::weeks(1))
+        }
+        ScheduleKind::Daily | ScheduleKind::Event => (start, start + Duration::days(1)),
+    }
+}
+
+/// Returns the number of seconds until the next calendar rotation.
+pub fn time_left(kind: ScheduleKind) -> i64 {
+    let now = Utc::now();
+    (period(kind, now).1 - now).num_seconds()
+}
+
+/// Returns the start and end of the rotation `now` falls in, taking the duration of the
+/// current event into account.
+pub async fn rotation(
+    pool: &PgPool,
+    kind: ScheduleKind,
+    now: DateTime<Utc>,
+) -> Result<(DateTime<Utc>, DateTime<Utc>)> {
+    if kind != ScheduleKind::Event {
+        return Ok(period(kind, now));
+    }
+
+    let current = ScheduledLevel::current(pool, kind).await?;
+
+    let event = match current.as_ref().and_then(|current| current.event_id) {
+        Some(event_id) => Event::get(pool, event_id).await?,
+        None => None,
+    };
+
+    Ok(event_rotation(
+        period(kind, now),
+        started_event(current.as_ref(), event.as_ref()),
+        now,
+    ))
+}
+
+/// Returns the start time and event definition of the current event level, if it has both.
+fn started_event<'a>(
+    current: Option<&ScheduledLevel>,
+    event: Option<&'a Event>,
+) -> Option<(DateTime<Utc>, &'a Event)> {
+    current.and_then(|current| current.starts_at).zip(event)
+}
+
+/// Returns the rotation of the event level given the calendar `period`, and the start time and
+/// definition of the current event level if it has both.
+///
+/// A running event lasts for its duration. Once it's over, the next one is due right away,
+/// unless the calendar period it ended in isn't over yet.
+pub fn event_rotation(
+    period: (DateTime<Utc>, DateTime<Utc>),
+    current: Option<(DateTime<Utc>, &Event)>,
+    now: DateTime<Utc>,
+) -> (DateTime<Utc>, DateTime<Utc>) {
+    let Some((starts_at, event)) = current else {
+        return period;
+    };
+
+    let ends_at = event.ends_at(starts_at);
+
+    if ends_at > now {
+        (starts_at, ends_at)
+    } else {
+        (ends_at, period.1.max(ends_at))
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+struct RotationState {
+    period_start: Option<DateTime<Utc>>,
+    filled: bool,
+    warned: bool,
+}
+
+/// Rotates the scheduled levels forever. Meant to be spawned once at startup.
+pub async fn run(pool: PgPool) {
+    let mut states = [RotationState::default(); 3];
+
+    loop {
+        let now = Utc::now();
+
+        let mut next_rotation = now + POLL_INTERVAL;
+
+        for (kind, state) in ScheduleKind::ALL.into_iter().zip(&mut states) {
+            match rotate(&pool, kind, state, now).await {
+                Ok(end) => next_rotation = next_rotation.min(end),
+                Err(e) => error!("Failed to rotate the {}: {e:#}", kind.name()),
+            }
+        }
+
+        let wait = (next_rotation - Utc::now()).to_std().unwrap_or_default();
+
+        tokio::time::sleep(wait).await;
+    }
+}
+
+async fn rotate(
+    pool: &PgPool,
+    kind: ScheduleKind,
+    state: &mut RotationState,
+    now: DateTime<Utc>,
+) -> Result<DateTime<Utc>> {
+    let (start, end) = rotation(pool, kind, now).await?;
+
+    if state.period_start != Some(start) {
+        *state = RotationState {
+            period_start: Some(start),
+            ..Default::default()
+        };
+    }
+
+    if state.filled {
+        return Ok(end);
+    }
+
+    if !ScheduledLevel::exists_between(pool, kind, start, end).await? {
+        let Some(promoted) = ScheduledLevel::promote(pool, kind, now).await? else {
+            if !state.warned {
+                warn!(
+                    "The {} queue is empty, keeping the previous one",
+                    kind.name()
+                );
+                state.warned = true;
+            }
+
+            return Ok(end);
+        };
+
+        info!("Level {} is the new {}", promoted.level_id, kind.name());
+    }
+
+    state.filled = true;
+
+    // Being picked is worth a creator point
+    if let Some(current) = ScheduledLevel::current(pool, kind).await? {
+        let level = Level::get(pool, current.level_id).await?;
+        creator_points::update(pool, level.user_id).await?;
+    }
+
+    if ScheduledLevel::get_queue(pool, kind).await?.is_empty() {
+        warn!(
+            "The {} queue has run dry, nothing is queued after the current one",
+            kind.name()
+        );
+    }
+
+    // A promoted event level brings its own duration
+    let (start, end) = rotation(pool, kind, now).await?;
+    state.period_start = Some(start);
+
+    Ok(end)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn at(day: u32, hour: u32) -> DateTime<Utc> {
+        // 2024-01-01 is a Monday
+        Utc.with_ymd_and_hms(2024, 1, day, hour, 0, 0).unwrap()
+    }
+
+    fn event(duration: i32) -> Event {
+        Event {
+            id: 1,
+            number: 1,
+            duration,
+            rewards: "1,100".to_string(),
+            created_by: None,
+            created_at: at(1, 0),
+        }
+    }
+
+    #[test]
+    fn daily_rotates_at_the_rotation_hour() {
+        assert_eq!(
+            period_at(ScheduleKind::Daily, at(3, 5), 6),
+            (at(2, 6), at(3, 6))
+        );
+        assert_eq!(
+            period_at(ScheduleKind::Daily, at(3, 6), 6),
+            (at(3, 6), at(4, 6))
+        );
+        assert_eq!(
+            period_at(ScheduleKind::Event, at(3, 23), 0),
+            (at(3, 0), at(4, 0))
+        );
+    }
+
+    #[test]
+    fn weekly_rotates_on_monday() {
+        // Sunday, and Monday before the rotation hour, are still in the previous week
+        assert_eq!(
+            period_at(ScheduleKind::Weekly, at(7, 12), 6),
+            (at(1, 6), at(8, 6))
+        );
+        assert_eq!(
+            period_at(ScheduleKind::Weekly, at(8, 5), 6),
+            (at(1, 6), at(8, 6))
+        );
+        assert_eq!(
+            period_at(ScheduleKind::Weekly, at(8, 6), 6),
+            (at(8, 6), at(15, 6))
+        );
+        assert_eq!(
+            period_at(ScheduleKind::Weekly, at(10, 0), 6),
+            (at(8, 6), at(15, 6))
+        );
+    }
+
+    #[test]
+    fn event_without_definition_uses_the_period() {
+        let period = (at(3, 0), at(4, 0));
+        assert_eq!(event_rotation(period, None, at(3, 12)), period);
+    }
+
+    #[test]
+    fn running_event_lasts_for_its_duration() {
+        let period = (at(3, 0), at(4, 0));
+        let event = event(2 * 86400);
+
+        // Running past the end of the calendar period
+        assert_eq!(
+            event_rotation(period, Some((at(2, 12), &event)), at(3, 12)),
+            (at(2, 12), at(4, 12))
+        );
+    }
+
+    #[test]
+    fn ended_event_is_replaced() {
+        let event = event(3600);
+
+        // Ended before the end of the calendar period, the next one lasts until then
+        assert_eq!(
+            event_rotation((at(3, 0), at(4, 0)), Some((at(3, 10), &event)), at(3, 12)),
+            (at(3, 11), at(4, 0))
+        );
+
+        // Ended after the end of the period it started in, the next one is due right away
+        let event = self::event(30 * 3600);
+        assert_eq!(
+            event_rotation((at(4, 0), at(5, 0)), Some((at(2, 20), &event)), at(4, 12)),
+            (at(4, 2), at(5, 0))
+        );
+    }
+}
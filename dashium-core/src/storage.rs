@@ -0,0 +1,5 @@
+mod level;
+pub use crate::storage::level::CompressedLevelStore;
+pub use crate::storage::level::FsLevelStore;
+pub use crate::storage::level::LevelStore;
+pub use crate::storage::level::level_store;
@@ -0,0 +1,166 @@
+use std::{
+    io::{ErrorKind, Read, Write},
+    path::{Path, PathBuf},
+    sync::LazyLock,
+};
+
+use anyhow::Result;
+use async_trait::async_trait;
+use flate2::{Compression, read::GzDecoder, write::GzEncoder};
+use tokio::{fs, io::AsyncWriteExt};
+
+use crate::{
+    config::config,
+    util::{random_token, salt_and_sha1},
+};
+
+static LEVEL_STORE: LazyLock<Box<dyn LevelStore>> = LazyLock::new(|| {
+    let config = config();
+
+    match config.level_store.as_str() {
+        "compressed" => Box::new(CompressedLevelStore::new(&config.levels_dir)),
+        _ => Box::new(FsLevelStore::new(&config.levels_dir)),
+    }
+});
+
+/// Returns the level store selected by the `LEVEL_STORE` setting.
+pub fn level_store() -> &'static dyn LevelStore {
+    LEVEL_STORE.as_ref()
+}
+
+/// Storage for level strings, keyed by level ID.
+#[async_trait]
+pub trait LevelStore: Send + Sync {
+    async fn get(&self, level_id: i32) -> Result<String>;
+    async fn put(&self, level_id: i32, level_string: &str) -> Result<()>;
+    async fn delete(&self, level_id: i32) -> Result<()>;
+}
+
+/// Stores each level string as plain text in `{root}/{level_id}.level`.
+pub struct FsLevelStore {
+    root: PathBuf,
+}
+
+impl FsLevelStore {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    fn path(&self, level_id: i32) -> PathBuf {
+        self.root.join(format!("{level_id}.level"))
+    }
+}
+
+#[async_trait]
+impl LevelStore for FsLevelStore {
+    async fn get(&self, level_id: i32) -> Result<String> {
+        Ok(fs::read_to_string(self.path(level_id)).await?)
+    }
+
+    async fn put(&self, level_id: i32, level_string: &str) -> Result<()> {
+        fs::create_dir_all(&self.root).await?;
+        write_atomic(&self.path(level_id), level_string.as_bytes()).await?;
+        Ok(())
+    }
+
+    async fn delete(&self, level_id: i32) -> Result<()> {
+        remove_if_exists(self.path(level_id)).await
+    }
+}
+
+/// Stores gzip-compressed level strings in `{root}/objects/{sha1}.gz`,
+/// so identical level strings are only kept once.
+/// Each level points to its object through `{root}/refs/{level_id}`.
+///
+/// Objects are never removed, since other levels may still refer to them.
+pub struct CompressedLevelStore {
+    root: PathBuf,
+}
+
+impl CompressedLevelStore {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    fn ref_path(&self, level_id: i32) -> PathBuf {
+        self.root.join("refs").join(level_id.to_string())
+    }
+
+    fn object_path(&self, hash: &str) -> PathBuf {
+        self.root.join("objects").join(format!("{hash}.gz"))
+    }
+}
+
+#[async_trait]
+impl LevelStore for CompressedLevelStore {
+    async fn get(&self, level_id: i32) -> Result<String> {
+        let hash = fs::read_to_string(self.ref_path(level_id)).await?;
+        let compressed = fs::read(self.object_path(hash.trim())).await?;
+
+        let level_string = tokio::task::spawn_blocking(move || {
+            let mut level_string = String::new();
+            GzDecoder::new(compressed.as_slice()).read_to_string(&mut level_string)?;
+            anyhow::Ok(level_string)
+        })
+        .await??;
+
+        Ok(level_string)
+    }
+
+    async fn put(&self, level_id: i32, level_string: &str) -> Result<()> {
+        let hash = salt_and_sha1(level_string, "");
+        let object_path = self.object_path(&hash);
+
+        if !fs::try_exists(&object_path).await? {
+            let level_string = level_string.to_string();
+            let compressed = tokio::task::spawn_blocking(move || {
+                let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+                encoder.write_all(level_string.as_bytes())?;
+                anyhow::Ok(encoder.finish()?)
+            })
+            .await??;
+
+            fs::create_dir_all(self.root.join("objects")).await?;
+            write_atomic(&object_path, &compressed).await?;
+        }
+
+        fs::create_dir_all(self.root.join("refs")).await?;
+        write_atomic(&self.ref_path(level_id), hash.as_bytes()).await?;
+        Ok(())
+    }
+
+    async fn delete(&self, level_id: i32) -> Result<()> {
+        remove_if_exists(self.ref_path(level_id)).await
+    }
+}
+
+/// Writes a file through a temporary one in the same directory, so a crash midway
+/// never leaves a truncated file behind.
+async fn write_atomic(path: &Path, contents: &[u8]) -> Result<()> {
+    let mut tmp_name = path.file_name().unwrap_or_default().to_os_string();
+    tmp_name.push(format!(".{}.tmp", random_token()));
+    let tmp_path = path.with_file_name(tmp_name);
+
+    let written = async {
+        let mut file = fs::File::create(&tmp_path).await?;
+        file.write_all(contents).await?;
+        file.sync_all().await?;
+        fs::rename(&tmp_path, path).await
+    }
+    .await;
+
+    if let Err(e) = written {
+        // Best effort, the write error is the one worth reporting
+        let _ = fs::remove_file(&tmp_path).await;
+        return Err(e.into());
+    }
+
+    Ok(())
+}
+
+async fn remove_if_exists(path: PathBuf) -> Result<()> {
+    match fs::remove_file(path).await {
+        Err(e) if e.kind() != ErrorKind::NotFound => Err(e.into()),
+        _ => Ok(()),
+    }
+}
@@ -1,13 +1,14 @@
 use anyhow::Result;
 use argon2::{
     Argon2,
-    password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString, rand_core::OsRng},
+    password_hash::{
+        PasswordHasher, SaltString,
+        rand_core::{OsRng, RngCore},
+    },
 };
 use base64::{Engine as _, engine::general_purpose::URL_SAFE};
-use chrono::{Datelike, Duration, Local, Weekday};
 use serde::{Deserialize, Deserializer};
 use sha1::{Digest, Sha1};
-use sqlx::PgPool;
 
 /// Returns `true` if a string is ASCII alphanumeric.
 pub fn is_ascii_alphanumeric(input: &str) -> bool {
@@ -42,16 +43,21 @@ pub fn hash_gjp2(gjp2: &str) -> Result<String> {
     Ok(password_hash)
 }
 
-/// Returns `true` if the given GJP2 matches the one stored in the database.
-pub async fn verify_gjp2(pool: &PgPool, user_id: i32, gjp2: &str) -> Result<bool> {
-    let argon2 = Argon2::default();
-    let hash = sqlx::query_scalar!("SELECT hash FROM users WHERE id = $1", user_id)
-        .fetch_one(pool)
-        .await?;
+/// Generates a random 64 character hex token.
+pub fn random_token() -> String {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
 
-    let hash = PasswordHash::new(&hash).expect("Failed to parse hash");
+/// Returns a random number between `min` and `max`, both included.
+pub fn random_range(min: u32, max: u32) -> u32 {
+    let (min, max) = if min <= max { (min, max) } else { (max, min) };
 
-    Ok(argon2.verify_password(gjp2.as_bytes(), &hash).is_ok())
+    // Done in 64 bits, since the span of the full range doesn't fit in 32
+    let span = u64::from(max - min) + 1;
+
+    min + (OsRng.next_u64() % span) as u32
 }
 
 /// Encodes a string as Base64.
@@ -75,42 +81,29 @@ pub fn cyclic_xor(data: &[u8], key: &[u8]) -> Result<String> {
     Ok(String::from_utf8(xor)?)
 }
 
-/// Returns the time in seconds to the next midnight (00:00).
-pub fn time_until_midnight() -> String {
-    let now = Local::now();
-    (now + Duration::days(1))
-        .date_naive()
-        .and_hms_opt(0, 0, 0)
-        .unwrap()
-        .and_local_timezone(Local)
-        .unwrap()
-        .signed_duration_since(now)
-        .num_seconds()
-        .to_string()
+/// Returns `true` if a client checksum matches the given values.
+///
+/// A checksum is the SHA1 of the concatenated values and a salt, XORed with a key and
+/// Base64 encoded.
+pub fn verify_chk(chk: &str, values: &[impl AsRef<str>], salt: &str, key: &[u8]) -> bool {
+    let Ok(decoded) = base64_decode(chk) else {
+        return false;
+    };
+
+    let values: String = values.iter().map(AsRef::as_ref).collect();
+    let expected = salt_and_sha1(&values, salt);
+
+    cyclic_xor(&decoded, key).is_ok_and(|hash| hash == expected)
 }
 
-/// Returns the time in seconds to the next Monday midnight (00:00).
-pub fn time_until_monday() -> String {
-    let now = Local::now();
-
-    let days = match now.weekday() {
-        Weekday::Mon => 7,
-        Weekday::Tue => 6,
-        Weekday::Wed => 5,
-        Weekday::Thu => 4,
-        Weekday::Fri => 3,
-        Weekday::Sat => 2,
-        Weekday::Sun => 1,
-    };
+/// Decodes the `chk` of reward requests, which is 5 random characters followed by a value XORed
+/// with a key and Base64 encoded. Returns `None` if it is malformed.
+pub fn decode_chk(chk: &str, key: &[u8]) -> Option<String> {
+    let encoded = chk.get(5..)?;
+    let decoded = cyclic_xor(&base64_decode(encoded).ok()?, key).ok()?;
 
-    (now.date_naive() + Duration::days(days as i64))
-        .and_hms_opt(0, 0, 0)
-        .unwrap()
-        .and_local_timezone(Local)
-        .unwrap()
-        .signed_duration_since(now)
-        .num_seconds()
-        .to_string()
+    // It is sent back in the response, where separators would break the format
+    (!decoded.is_empty() && is_ascii_alphanumeric(&decoded)).then_some(decoded)
 }
 
 /// Deserializes a `0` or `1` as a `bool` with the `#[serde(deserialize_with = "int_to_bool")]` attribute.
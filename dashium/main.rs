@@ -2,7 +2,7 @@ use anyhow::Result;
 use axum::Router;
 use dotenvy::dotenv;
 use sqlx::PgPool;
-use std::{env, sync::Arc, time::Duration};
+use std::{env, net::SocketAddr, sync::Arc, time::Duration};
 use tokio::net::TcpListener;
 use tower_governor::{GovernorLayer, governor::GovernorConfigBuilder};
 
@@ -18,7 +18,15 @@ use tracing_subscriber::{EnvFilter, layer::SubscriberExt, util::SubscriberInitEx
 #[tokio::main]
 async fn main() -> Result<()> {
     setup_logging();
+    dashium_core::config::config().validate()?;
     let pool = setup_db().await?;
+
+    if let Some(command) = env::args().nth(1) {
+        return run_command(&pool, &command).await;
+    }
+
+    tokio::spawn(dashium_core::services::scheduler::run(pool.clone()));
+
     let app = setup_app(pool);
     let listener = TcpListener::bind("127.0.0.1:2207").await?;
     tracing::info!("Server running at http://127.0.0.1:2207");
@@ -26,7 +34,11 @@ async fn main() -> Result<()> {
     #[cfg(debug_assertions)]
     tracing::warn!("Running in debug mode. Use only for local development.");
 
-    axum::serve(listener, app).await?;
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await?;
     Ok(())
 }
 
@@ -38,6 +50,91 @@ async fn setup_db() -> Result<PgPool> {
     Ok(pool)
 }
 
+async fn run_command(pool: &PgPool, command: &str) -> Result<()> {
+    match command {
+        "recalculate-cp" => {
+            let count = dashium_core::services::creator_points::update_all(pool).await?;
+            tracing::info!("Recalculated creator points for {count} users");
+        }
+        "add-quest" => {
+            let args: Vec<String> = env::args().skip(2).collect();
+
+            let [quest_type, amount, reward, name] = args.as_slice() else {
+                anyhow::bail!("Usage: add-quest <type> <amount> <reward> <name>");
+            };
+
+            let quest_type: i16 = quest_type.parse()?;
+            let amount: i32 = amount.parse()?;
+            let reward: i32 = reward.parse()?;
+
+            if !(1..=3).contains(&quest_type) {
+                anyhow::bail!("Quest types are 1 for orbs, 2 for coins and 3 for stars");
+            }
+
+            if amount <= 0 || reward <= 0 {
+                anyhow::bail!("The amount and reward must be positive");
+            }
+
+            if name.is_empty() || name.chars().count() > 64 {
+                anyhow::bail!("Quest names must be 1 to 64 characters long");
+            }
+
+            if name.contains([',', ':', '|']) {
+                anyhow::bail!("Quest names can't contain ',', ':' or '|'");
+            }
+
+            let quest_id =
+                dashium_core::models::Quest::create(pool, quest_type, amount, reward, name).await?;
+
+            tracing::info!("Added quest {quest_id}");
+        }
+        "add-secret-reward" => {
+            let args: Vec<String> = env::args().skip(2).collect();
+
+            let (code, rewards, uses, expires_at) = match args.as_slice() {
+                [code, rewards, uses] => (code, rewards, uses, None),
+                [code, rewards, uses, expires_at] => (code, rewards, uses, Some(expires_at)),
+                _ => anyhow::bail!(
+                    "Usage: add-secret-reward <code> <rewards> <single|unlimited> [expires_at]"
+                ),
+            };
+
+            // Codes entered in the vault are trimmed before being looked up
+            let code = code.trim();
+
+            if code.is_empty() || code.chars().count() > 64 {
+                anyhow::bail!("Codes must be 1 to 64 characters long");
+            }
+
+            if rewards.is_empty() {
+                anyhow::bail!("Secret rewards must give at least one item");
+            }
+
+            if !dashium_core::models::Event::is_valid_rewards(rewards) {
+                anyhow::bail!("Rewards must be written as type,amount,type,amount,...");
+            }
+
+            let single_use = match uses.as_str() {
+                "single" => true,
+                "unlimited" => false,
+                _ => anyhow::bail!("Uses must be either 'single' or 'unlimited'"),
+            };
+
+            let expires_at = expires_at.map(|date| date.parse()).transpose()?;
+
+            let reward_id = dashium_core::models::SecretReward::create(
+                pool, code, rewards, single_use, expires_at,
+            )
+            .await?;
+
+            tracing::info!("Added secret reward {reward_id}");
+        }
+        _ => anyhow::bail!("Unknown command: {command}"),
+    }
+
+    Ok(())
+}
+
 fn setup_app(pool: PgPool) -> Router {
     #[cfg(debug_assertions)]
     let key_extractor = GlobalKeyExtractor;
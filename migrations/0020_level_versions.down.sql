@@ -0,0 +1 @@
+DROP TABLE level_versions;
\ No newline at end of file
@@ -0,0 +1,10 @@
+CREATE TABLE level_versions (
+    id SERIAL PRIMARY KEY,
+    level_id INT NOT NULL,
+    version INT NOT NULL,
+    level_string TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    UNIQUE (level_id, version),
+    FOREIGN KEY (level_id) REFERENCES levels (id) ON DELETE CASCADE
+);
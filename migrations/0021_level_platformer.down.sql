@@ -0,0 +1 @@
+ALTER TABLE levels DROP COLUMN platformer;
\ No newline at end of file
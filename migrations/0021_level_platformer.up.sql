@@ -0,0 +1 @@
+ALTER TABLE levels ADD COLUMN platformer BOOLEAN NOT NULL DEFAULT FALSE;
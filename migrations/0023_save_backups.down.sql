@@ -0,0 +1,22 @@
+DROP VIEW user_view;
+
+ALTER TABLE users ADD COLUMN save_data TEXT NOT NULL DEFAULT '';
+
+-- Compressed backups can't be decoded in SQL, so only uncompressed ones are carried back
+UPDATE users
+SET save_data = latest.data
+FROM (
+    SELECT DISTINCT ON (user_id) user_id, convert_from(data, 'UTF8') AS data
+    FROM save_backups
+    WHERE compressed = FALSE
+    ORDER BY user_id, id DESC
+) AS latest
+WHERE users.id = latest.user_id;
+
+CREATE VIEW user_view AS
+SELECT *, ROW_NUMBER () OVER (
+    ORDER BY stars DESC
+) AS rank
+FROM users;
+
+DROP TABLE save_backups;
\ No newline at end of file
@@ -0,0 +1,26 @@
+CREATE TABLE save_backups (
+    id SERIAL PRIMARY KEY,
+    user_id INT NOT NULL,
+    data BYTEA NOT NULL,
+    compressed BOOLEAN NOT NULL DEFAULT TRUE,
+    size INT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE
+);
+
+-- Existing backups are carried over uncompressed
+INSERT INTO save_backups (user_id, data, compressed, size)
+SELECT id, convert_to(save_data, 'UTF8'), FALSE, octet_length(save_data)
+FROM users
+WHERE save_data <> '';
+
+DROP VIEW user_view;
+
+ALTER TABLE users DROP COLUMN save_data;
+
+CREATE VIEW user_view AS
+SELECT *, ROW_NUMBER () OVER (
+    ORDER BY stars DESC
+) AS rank
+FROM users;
\ No newline at end of file
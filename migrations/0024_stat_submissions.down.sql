@@ -0,0 +1,11 @@
+DROP VIEW user_view;
+
+ALTER TABLE users DROP COLUMN leaderboard_banned;
+
+CREATE VIEW user_view AS
+SELECT *, ROW_NUMBER () OVER (
+    ORDER BY stars DESC
+) AS rank
+FROM users;
+
+DROP TABLE stat_submissions;
\ No newline at end of file
@@ -0,0 +1,25 @@
+CREATE TABLE stat_submissions (
+    id SERIAL PRIMARY KEY,
+    user_id INT NOT NULL,
+    stars INT NOT NULL,
+    moons INT NOT NULL,
+    demons INT NOT NULL,
+    diamonds INT NOT NULL,
+    coins INT NOT NULL,
+    user_coins INT NOT NULL,
+    verdict VARCHAR(20) NOT NULL,
+    reason TEXT NOT NULL DEFAULT '',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE
+);
+
+DROP VIEW user_view;
+
+ALTER TABLE users ADD COLUMN leaderboard_banned BOOLEAN NOT NULL DEFAULT FALSE;
+
+CREATE VIEW user_view AS
+SELECT *, ROW_NUMBER () OVER (
+    ORDER BY stars DESC
+) AS rank
+FROM users;
\ No newline at end of file
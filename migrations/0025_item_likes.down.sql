@@ -0,0 +1 @@
+DROP TABLE item_likes;
\ No newline at end of file
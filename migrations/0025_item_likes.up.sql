@@ -0,0 +1,11 @@
+CREATE TABLE item_likes (
+    id SERIAL PRIMARY KEY,
+    user_id INT NOT NULL,
+    item_type SMALLINT NOT NULL,
+    item_id INT NOT NULL,
+    is_like BOOLEAN NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    UNIQUE (user_id, item_type, item_id),
+    FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE
+);
\ No newline at end of file
@@ -0,0 +1,2 @@
+DROP TABLE level_daily_downloads;
+DROP TABLE level_downloads;
\ No newline at end of file
@@ -0,0 +1,24 @@
+CREATE TABLE level_downloads (
+    id SERIAL PRIMARY KEY,
+    level_id INT NOT NULL,
+    user_id INT,
+    ip VARCHAR(45),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    -- Accounts are counted once by ID, guests once by IP
+    UNIQUE NULLS NOT DISTINCT (level_id, user_id, ip),
+    FOREIGN KEY (level_id) REFERENCES levels (id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE
+);
+
+CREATE TABLE level_daily_downloads (
+    level_id INT NOT NULL,
+    day DATE NOT NULL,
+    downloads INT NOT NULL DEFAULT 0,
+
+    PRIMARY KEY (level_id, day),
+    FOREIGN KEY (level_id) REFERENCES levels (id) ON DELETE CASCADE
+);
+
+-- Trending only looks at the last few days
+CREATE INDEX level_daily_downloads_day_idx ON level_daily_downloads (day);
\ No newline at end of file
@@ -0,0 +1,5 @@
+ALTER TABLE levels DROP COLUMN hidden;
+
+ALTER TABLE level_reports
+    DROP COLUMN user_id,
+    DROP COLUMN ip;
\ No newline at end of file
@@ -0,0 +1,11 @@
+-- Older reports have no reporter, so only one of them is kept per level
+DELETE FROM level_reports a
+USING level_reports b
+WHERE a.level_id = b.level_id AND a.id > b.id;
+
+ALTER TABLE level_reports
+    ADD COLUMN user_id INT REFERENCES users (id) ON DELETE CASCADE,
+    ADD COLUMN ip VARCHAR(45),
+    ADD UNIQUE NULLS NOT DISTINCT (level_id, user_id, ip);
+
+ALTER TABLE levels ADD COLUMN hidden BOOLEAN NOT NULL DEFAULT FALSE;
\ No newline at end of file
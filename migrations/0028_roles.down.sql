@@ -0,0 +1,20 @@
+ALTER TABLE comments ADD COLUMN chat_color VARCHAR(30) NOT NULL DEFAULT '255,255,255';
+
+DROP VIEW user_view;
+
+ALTER TABLE users ADD COLUMN mod_level SMALLINT NOT NULL DEFAULT 0;
+
+UPDATE users SET mod_level = roles.badge
+FROM roles
+WHERE roles.id = users.role_id;
+
+ALTER TABLE users DROP COLUMN role_id;
+
+CREATE VIEW user_view AS
+SELECT *, ROW_NUMBER () OVER (
+    ORDER BY stars DESC
+) AS rank
+FROM users;
+
+DROP TABLE permissions;
+DROP TABLE roles;
\ No newline at end of file
@@ -0,0 +1,51 @@
+CREATE TABLE roles (
+    id SERIAL PRIMARY KEY,
+    name VARCHAR(32) NOT NULL UNIQUE,
+    badge SMALLINT NOT NULL DEFAULT 0,
+    chat_color VARCHAR(11) NOT NULL DEFAULT '255,255,255',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE permissions (
+    role_id INT NOT NULL,
+    permission VARCHAR(32) NOT NULL,
+
+    PRIMARY KEY (role_id, permission),
+    FOREIGN KEY (role_id) REFERENCES roles (id) ON DELETE CASCADE
+);
+
+INSERT INTO roles (name, badge, chat_color) VALUES
+    ('Moderator', 1, '255,255,255'),
+    ('Elder Moderator', 2, '75,255,75');
+
+INSERT INTO permissions (role_id, permission)
+SELECT roles.id, permission
+FROM roles, UNNEST(ARRAY['suggest', 'edit_levels', 'delete_comments', 'review_reports']) AS permission
+WHERE roles.name = 'Moderator';
+
+INSERT INTO permissions (role_id, permission)
+SELECT roles.id, permission
+FROM roles, UNNEST(ARRAY[
+    'suggest', 'rate', 'feature', 'edit_levels', 'delete_comments',
+    'review_reports', 'inspect_accounts', 'set_daily', 'ban'
+]) AS permission
+WHERE roles.name = 'Elder Moderator';
+
+DROP VIEW user_view;
+
+ALTER TABLE users ADD COLUMN role_id INT REFERENCES roles (id) ON DELETE SET NULL;
+
+UPDATE users SET role_id = roles.id
+FROM roles
+WHERE roles.badge = users.mod_level;
+
+ALTER TABLE users DROP COLUMN mod_level;
+
+CREATE VIEW user_view AS
+SELECT users.*, COALESCE(roles.badge, 0::SMALLINT) AS mod_level, ROW_NUMBER () OVER (
+    ORDER BY stars DESC
+) AS rank
+FROM users
+LEFT JOIN roles ON roles.id = users.role_id;
+
+ALTER TABLE comments DROP COLUMN chat_color;
\ No newline at end of file
@@ -0,0 +1,16 @@
+DROP VIEW user_view;
+
+ALTER TABLE users ADD COLUMN leaderboard_banned BOOLEAN NOT NULL DEFAULT FALSE;
+
+UPDATE users SET leaderboard_banned = TRUE
+WHERE id IN (SELECT user_id FROM active_restrictions WHERE kind = 'leaderboard');
+
+CREATE VIEW user_view AS
+SELECT users.*, COALESCE(roles.badge, 0::SMALLINT) AS mod_level, ROW_NUMBER () OVER (
+    ORDER BY stars DESC
+) AS rank
+FROM users
+LEFT JOIN roles ON roles.id = users.role_id;
+
+DROP VIEW active_restrictions;
+DROP TABLE restrictions;
\ No newline at end of file
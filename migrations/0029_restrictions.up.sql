@@ -0,0 +1,39 @@
+CREATE TABLE restrictions (
+    id SERIAL PRIMARY KEY,
+    user_id INT NOT NULL,
+    kind VARCHAR(20) NOT NULL,
+    reason TEXT NOT NULL DEFAULT '',
+    moderator_id INT,
+    lifted BOOLEAN NOT NULL DEFAULT FALSE,
+    expires_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE,
+    FOREIGN KEY (moderator_id) REFERENCES users (id) ON DELETE SET NULL
+);
+
+CREATE INDEX restrictions_user_id_kind_idx ON restrictions (user_id, kind);
+
+CREATE VIEW active_restrictions AS
+SELECT * FROM restrictions
+WHERE NOT lifted AND (expires_at IS NULL OR expires_at > NOW());
+
+INSERT INTO restrictions (user_id, kind, reason)
+SELECT id, 'leaderboard', 'Too many rejected stat submissions'
+FROM users
+WHERE leaderboard_banned;
+
+DROP VIEW user_view;
+
+ALTER TABLE users DROP COLUMN leaderboard_banned;
+
+-- Users banned from the leaderboards are left out of the ranking, and have no rank
+CREATE VIEW user_view AS
+SELECT users.*, COALESCE(roles.badge, 0::SMALLINT) AS mod_level, ranks.rank
+FROM users
+LEFT JOIN roles ON roles.id = users.role_id
+LEFT JOIN (
+    SELECT id, ROW_NUMBER () OVER (ORDER BY stars DESC) AS rank
+    FROM users
+    WHERE id NOT IN (SELECT user_id FROM active_restrictions WHERE kind = 'leaderboard')
+) ranks ON ranks.id = users.id;
\ No newline at end of file
@@ -0,0 +1,11 @@
+ALTER TABLE daily_levels
+    DROP COLUMN starts_at,
+    DROP COLUMN queued_by;
+
+ALTER TABLE weekly_demons
+    DROP COLUMN starts_at,
+    DROP COLUMN queued_by;
+
+ALTER TABLE event_levels
+    DROP COLUMN starts_at,
+    DROP COLUMN queued_by;
\ No newline at end of file
@@ -0,0 +1,23 @@
+ALTER TABLE daily_levels
+    ADD COLUMN starts_at TIMESTAMPTZ,
+    ADD COLUMN queued_by INT REFERENCES users (id) ON DELETE SET NULL;
+
+UPDATE daily_levels SET starts_at = created_at;
+
+CREATE INDEX daily_levels_starts_at_idx ON daily_levels (starts_at);
+
+ALTER TABLE weekly_demons
+    ADD COLUMN starts_at TIMESTAMPTZ,
+    ADD COLUMN queued_by INT REFERENCES users (id) ON DELETE SET NULL;
+
+UPDATE weekly_demons SET starts_at = created_at;
+
+CREATE INDEX weekly_demons_starts_at_idx ON weekly_demons (starts_at);
+
+ALTER TABLE event_levels
+    ADD COLUMN starts_at TIMESTAMPTZ,
+    ADD COLUMN queued_by INT REFERENCES users (id) ON DELETE SET NULL;
+
+UPDATE event_levels SET starts_at = created_at;
+
+CREATE INDEX event_levels_starts_at_idx ON event_levels (starts_at);
\ No newline at end of file
@@ -0,0 +1,3 @@
+ALTER TABLE event_levels DROP COLUMN event_id;
+
+DROP TABLE events;
\ No newline at end of file
@@ -0,0 +1,12 @@
+CREATE TABLE events (
+    id SERIAL PRIMARY KEY,
+    number INT NOT NULL,
+    duration INT NOT NULL,
+    rewards VARCHAR(255) NOT NULL DEFAULT '',
+    created_by INT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    FOREIGN KEY (created_by) REFERENCES users (id) ON DELETE SET NULL
+);
+
+ALTER TABLE event_levels ADD COLUMN event_id INT REFERENCES events (id) ON DELETE SET NULL;
\ No newline at end of file
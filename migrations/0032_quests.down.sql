@@ -0,0 +1,3 @@
+DROP TABLE player_quests;
+DROP TABLE issued_quests;
+DROP TABLE quests;
\ No newline at end of file
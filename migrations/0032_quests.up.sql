@@ -0,0 +1,40 @@
+CREATE TABLE quests (
+    id SERIAL PRIMARY KEY,
+    quest_type SMALLINT NOT NULL,
+    amount INT NOT NULL,
+    reward INT NOT NULL,
+    name VARCHAR(64) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE issued_quests (
+    id SERIAL PRIMARY KEY,
+    quest_id INT NOT NULL,
+    day DATE NOT NULL,
+    slot SMALLINT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    -- Players get their own quests, so a slot can hold a different quest for each of them
+    UNIQUE (day, slot, quest_id),
+    FOREIGN KEY (quest_id) REFERENCES quests (id) ON DELETE CASCADE
+);
+
+CREATE TABLE player_quests (
+    id SERIAL PRIMARY KEY,
+    user_id INT,
+    udid VARCHAR(64),
+    day DATE NOT NULL,
+    slot SMALLINT NOT NULL,
+    issued_quest_id INT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    -- Accounts are tracked by ID, guests by UDID
+    UNIQUE NULLS NOT DISTINCT (user_id, udid, day, slot),
+    FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE,
+    FOREIGN KEY (issued_quest_id) REFERENCES issued_quests (id) ON DELETE CASCADE
+);
+
+INSERT INTO quests (quest_type, amount, reward, name) VALUES
+    (1, 500, 10, 'Orb Quest'),
+    (2, 6, 20, 'Coin Quest'),
+    (3, 10, 30, 'Star Quest');
\ No newline at end of file
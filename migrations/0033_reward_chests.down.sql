@@ -0,0 +1 @@
+DROP TABLE reward_chests;
\ No newline at end of file
@@ -0,0 +1,14 @@
+CREATE TABLE reward_chests (
+    id SERIAL PRIMARY KEY,
+    user_id INT,
+    udid VARCHAR(64),
+    small_count INT NOT NULL DEFAULT 0,
+    small_opened_at TIMESTAMPTZ,
+    large_count INT NOT NULL DEFAULT 0,
+    large_opened_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    -- Accounts are tracked by ID, guests by UDID
+    UNIQUE NULLS NOT DISTINCT (user_id, udid),
+    FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE
+);
\ No newline at end of file
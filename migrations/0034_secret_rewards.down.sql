@@ -0,0 +1,2 @@
+DROP TABLE secret_reward_redemptions;
+DROP TABLE secret_rewards;
\ No newline at end of file
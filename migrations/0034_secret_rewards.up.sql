@@ -0,0 +1,29 @@
+CREATE TABLE secret_rewards (
+    id SERIAL PRIMARY KEY,
+    code VARCHAR(64) NOT NULL,
+    rewards VARCHAR(255) NOT NULL,
+    single_use BOOLEAN NOT NULL DEFAULT TRUE,
+    expires_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Codes are matched regardless of case
+CREATE UNIQUE INDEX secret_rewards_code_idx ON secret_rewards (LOWER(code));
+
+CREATE TABLE secret_reward_redemptions (
+    id SERIAL PRIMARY KEY,
+    reward_id INT NOT NULL,
+    user_id INT,
+    udid VARCHAR(64),
+    -- Copied from the code when redeemed, later changes to it don't apply to past redemptions
+    single_use BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    FOREIGN KEY (reward_id) REFERENCES secret_rewards (id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE
+);
+
+-- Keeps two requests at once from both redeeming a single use code
+CREATE UNIQUE INDEX secret_reward_redemptions_single_use_idx
+ON secret_reward_redemptions (reward_id, user_id, udid) NULLS NOT DISTINCT
+WHERE single_use;
\ No newline at end of file